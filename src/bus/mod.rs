@@ -0,0 +1,262 @@
+use std::sync::{Arc, Mutex, Weak};
+
+use tokio::sync::Notify;
+
+use crate::{events::Event, metrics};
+
+use self::queue::{Class, Queue};
+
+pub use self::queue::Dropped;
+
+mod queue;
+
+#[derive(Default)]
+struct Shared {
+    queue: Mutex<Queue>,
+    closed: Mutex<bool>,
+    notify: Notify,
+    changes_only: bool,
+    states_only: bool,
+}
+
+#[derive(Default)]
+struct Subscriptions {
+    subscribers: Mutex<Vec<Weak<Shared>>>,
+    closed: Mutex<bool>,
+}
+
+impl Subscriptions {
+    fn close(&self) {
+        *self.closed.lock().unwrap() = true;
+
+        for shared in self.subscribers.lock().unwrap().iter() {
+            if let Some(shared) = shared.upgrade() {
+                *shared.closed.lock().unwrap() = true;
+                shared.notify.notify_one();
+            }
+        }
+    }
+}
+
+impl Drop for Subscriptions {
+    fn drop(&mut self) {
+        self.close();
+    }
+}
+
+#[derive(Clone, Default)]
+pub struct Publisher {
+    subscriptions: Arc<Subscriptions>,
+}
+
+pub struct Subscriber {
+    shared: Arc<Shared>,
+}
+
+pub fn new() -> Publisher {
+    Publisher::default()
+}
+
+impl Publisher {
+    pub fn subscribe(&self) -> Subscriber {
+        self.add_subscriber(false, false)
+    }
+
+    pub fn subscribe_states(&self) -> Subscriber {
+        self.add_subscriber(true, false)
+    }
+
+    // state events without those sent on startup, which report current states as if they changed
+    pub fn subscribe_changes(&self) -> Subscriber {
+        self.add_subscriber(true, true)
+    }
+
+    // subscribers receive what is queued and then end, events sent afterwards are discarded
+    pub fn close(&self) {
+        self.subscriptions.close();
+    }
+
+    pub fn send(&self, event: Event) {
+        self.publish(event, false);
+    }
+
+    pub fn send_initial(&self, event: Event) {
+        self.publish(event, true);
+    }
+
+    fn publish(&self, event: Event, initial: bool) {
+        if *self.subscriptions.closed.lock().unwrap() {
+            return;
+        }
+
+        metrics::event_published();
+
+        let mut subscribers = self.subscriptions.subscribers.lock().unwrap();
+        subscribers.retain(|shared| shared.strong_count() > 0);
+
+        for shared in subscribers.iter().filter_map(|shared| shared.upgrade()) {
+            if shared.states_only && queue::get_class(&event.event) != Class::State {
+                continue;
+            }
+
+            if shared.changes_only && initial {
+                continue;
+            }
+
+            if let Some(dropped) = shared.queue.lock().unwrap().push(event.clone()) {
+                metrics::bus_dropped(dropped);
+            }
+
+            shared.notify.notify_one();
+        }
+    }
+
+    fn add_subscriber(&self, states_only: bool, changes_only: bool) -> Subscriber {
+        let shared = Arc::new(Shared {
+            changes_only,
+            states_only,
+            ..Default::default()
+        });
+
+        self.subscriptions
+            .subscribers
+            .lock()
+            .unwrap()
+            .push(Arc::downgrade(&shared));
+
+        Subscriber { shared }
+    }
+}
+
+impl Subscriber {
+    pub async fn recv(&mut self) -> Option<Event> {
+        loop {
+            if let Some(event) = self.shared.queue.lock().unwrap().pop() {
+                return Some(event);
+            }
+
+            if *self.shared.closed.lock().unwrap() {
+                return None;
+            }
+
+            self.shared.notify.notified().await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod must {
+    use std::time::Duration;
+
+    use crate::events::{ContainerEvent, Event, EventType, LogLine};
+
+    fn create_event(event: EventType) -> Event {
+        Event {
+            container_name: "container".to_owned(),
+            event,
+        }
+    }
+
+    #[tokio::test]
+    async fn deliver_events_to_every_subscriber() {
+        // arrange
+        let publisher = super::new();
+        let mut first = publisher.subscribe();
+        let mut second = publisher.subscribe();
+
+        // act
+        publisher.send(create_event(EventType::State(ContainerEvent::Start)));
+
+        // assert
+        assert!(first.recv().await.is_some());
+        assert!(second.recv().await.is_some());
+    }
+
+    #[tokio::test]
+    async fn deliver_state_events_only_to_state_subscribers() {
+        // arrange
+        let publisher = super::new();
+        let mut subscriber = publisher.subscribe_states();
+
+        // act
+        publisher.send(create_event(EventType::CpuUsage(1.0)));
+        publisher.send(create_event(EventType::Log(LogLine::default())));
+        publisher.send(create_event(EventType::State(ContainerEvent::Stop)));
+
+        // assert
+        assert_eq!(
+            EventType::State(ContainerEvent::Stop),
+            subscriber.recv().await.unwrap().event
+        );
+    }
+
+    #[tokio::test]
+    async fn skip_initial_events_for_change_subscribers() {
+        // arrange
+        let publisher = super::new();
+        let mut subscriber = publisher.subscribe_changes();
+        let mut states = publisher.subscribe_states();
+
+        // act
+        publisher.send_initial(create_event(EventType::State(ContainerEvent::Create)));
+        publisher.send(create_event(EventType::State(ContainerEvent::Stop)));
+
+        // assert
+        assert_eq!(
+            EventType::State(ContainerEvent::Stop),
+            subscriber.recv().await.unwrap().event
+        );
+        assert_eq!(
+            EventType::State(ContainerEvent::Create),
+            states.recv().await.unwrap().event
+        );
+    }
+
+    #[tokio::test]
+    async fn close_subscribers_after_last_publisher_dropped() {
+        // arrange
+        let publisher = super::new();
+        let mut subscriber = publisher.subscribe();
+        let clone = publisher.clone();
+
+        // act
+        drop(publisher);
+        clone.send(create_event(EventType::State(ContainerEvent::Start)));
+        drop(clone);
+
+        // assert
+        let received = tokio::time::timeout(Duration::from_millis(100), async {
+            (subscriber.recv().await, subscriber.recv().await)
+        })
+        .await
+        .unwrap();
+
+        assert!(received.0.is_some());
+        assert!(received.1.is_none());
+    }
+
+    #[tokio::test]
+    async fn drain_queued_events_after_close() {
+        // arrange
+        let publisher = super::new();
+        let mut subscriber = publisher.subscribe();
+        publisher.send(create_event(EventType::State(ContainerEvent::Start)));
+
+        // act
+        publisher.close();
+        publisher.send(create_event(EventType::State(ContainerEvent::Stop)));
+
+        // assert
+        let received = tokio::time::timeout(Duration::from_millis(100), async {
+            (subscriber.recv().await, subscriber.recv().await)
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(
+            Some(EventType::State(ContainerEvent::Start)),
+            received.0.map(|event| event.event)
+        );
+        assert!(received.1.is_none());
+    }
+}
@@ -0,0 +1,198 @@
+use std::collections::{HashMap, VecDeque};
+
+use crate::events::{Event, EventType};
+
+const LOG_CAPACITY: usize = 1000;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Dropped {
+    Coalesced,
+    LogOverflow,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Class {
+    Log,
+    Metric,
+    State,
+}
+
+#[derive(Default)]
+pub struct Queue {
+    entries: VecDeque<Event>,
+    offset: u64,
+    metrics: HashMap<(String, String), u64>,
+    logs: usize,
+}
+
+impl Queue {
+    pub fn push(&mut self, event: Event) -> Option<Dropped> {
+        match get_class(&event.event) {
+            Class::Log => self.push_log(event),
+            Class::Metric => self.push_metric(event),
+            Class::State => {
+                self.entries.push_back(event);
+                None
+            }
+        }
+    }
+
+    pub fn pop(&mut self) -> Option<Event> {
+        let event = self.entries.pop_front()?;
+        let sequence = self.offset;
+        self.offset += 1;
+
+        match get_class(&event.event) {
+            Class::Log => self.logs -= 1,
+            Class::Metric => {
+                let key = get_key(&event);
+                if self.metrics.get(&key) == Some(&sequence) {
+                    self.metrics.remove(&key);
+                }
+            }
+            Class::State => {}
+        }
+
+        Some(event)
+    }
+
+    // metrics only matter with their latest value, thus a pending value is replaced in place
+    fn push_metric(&mut self, event: Event) -> Option<Dropped> {
+        let key = get_key(&event);
+        if let Some(sequence) = self.metrics.get(&key) {
+            let index = (sequence - self.offset) as usize;
+            self.entries[index] = event;
+
+            return Some(Dropped::Coalesced);
+        }
+
+        self.metrics
+            .insert(key, self.offset + self.entries.len() as u64);
+        self.entries.push_back(event);
+
+        None
+    }
+
+    fn push_log(&mut self, event: Event) -> Option<Dropped> {
+        if self.logs >= LOG_CAPACITY {
+            return Some(Dropped::LogOverflow);
+        }
+
+        self.logs += 1;
+        self.entries.push_back(event);
+
+        None
+    }
+}
+
+pub fn get_class(event: &EventType) -> Class {
+    match event {
+        EventType::Log(_) | EventType::LogStderr(_) | EventType::LogStdout(_) => Class::Log,
+        EventType::CpuStatistics(_)
+        | EventType::CpuUsage(_)
+        | EventType::Downtime(_)
+        | EventType::LogsSuppressed(_)
+        | EventType::MemoryStatistics(_)
+        | EventType::MemoryUsage(_)
+        | EventType::TimeSinceStart(_)
+        | EventType::Uptime24h(_)
+        | EventType::Uptime30d(_)
+        | EventType::Uptime7d(_) => Class::Metric,
+        _ => Class::State,
+    }
+}
+
+fn get_key(event: &Event) -> (String, String) {
+    (event.container_name.to_owned(), event.event.to_string())
+}
+
+#[cfg(test)]
+mod must {
+    use crate::events::{ContainerEvent, Event, EventType, LogLine};
+
+    use super::{Dropped, Queue, LOG_CAPACITY};
+
+    fn create_event(container_name: &str, event: EventType) -> Event {
+        Event {
+            container_name: container_name.to_owned(),
+            event,
+        }
+    }
+
+    #[test]
+    fn keep_every_state_event_in_order() {
+        // arrange
+        let mut queue = Queue::default();
+
+        // act
+        for _ in 0..5000 {
+            queue.push(create_event("a", EventType::State(ContainerEvent::Start)));
+        }
+        queue.push(create_event("a", EventType::State(ContainerEvent::Stop)));
+
+        // assert
+        let mut count = 0;
+        let mut last = None;
+        while let Some(event) = queue.pop() {
+            count += 1;
+            last = Some(event.event);
+        }
+
+        assert_eq!(5001, count);
+        assert_eq!(Some(EventType::State(ContainerEvent::Stop)), last);
+    }
+
+    #[test]
+    fn coalesce_pending_metrics_per_container() {
+        // arrange
+        let mut queue = Queue::default();
+        queue.push(create_event("a", EventType::CpuUsage(1.0)));
+        queue.push(create_event("a", EventType::State(ContainerEvent::Stop)));
+        queue.push(create_event("b", EventType::CpuUsage(2.0)));
+
+        // act
+        let dropped = queue.push(create_event("a", EventType::CpuUsage(3.0)));
+
+        // assert
+        assert_eq!(Some(Dropped::Coalesced), dropped);
+        assert_eq!(EventType::CpuUsage(3.0), queue.pop().unwrap().event);
+        assert_eq!(
+            EventType::State(ContainerEvent::Stop),
+            queue.pop().unwrap().event
+        );
+        assert_eq!(EventType::CpuUsage(2.0), queue.pop().unwrap().event);
+        assert!(queue.pop().is_none());
+    }
+
+    #[test]
+    fn queue_metrics_again_after_delivery() {
+        // arrange
+        let mut queue = Queue::default();
+        queue.push(create_event("a", EventType::MemoryUsage(1.0)));
+        queue.pop();
+
+        // act
+        let dropped = queue.push(create_event("a", EventType::MemoryUsage(2.0)));
+
+        // assert
+        assert_eq!(None, dropped);
+        assert_eq!(EventType::MemoryUsage(2.0), queue.pop().unwrap().event);
+    }
+
+    #[test]
+    fn drop_logs_beyond_capacity() {
+        // arrange
+        let mut queue = Queue::default();
+        for _ in 0..LOG_CAPACITY {
+            queue.push(create_event("a", EventType::Log(LogLine::default())));
+        }
+
+        // act
+        let dropped = queue.push(create_event("a", EventType::Log(LogLine::default())));
+        let state = queue.push(create_event("a", EventType::State(ContainerEvent::Die)));
+
+        // assert
+        assert_eq!(Some(Dropped::LogOverflow), dropped);
+        assert_eq!(None, state);
+    }
+}
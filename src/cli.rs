@@ -0,0 +1,111 @@
+use std::path::PathBuf;
+
+use structopt::StructOpt;
+
+use crate::configuration::Backend;
+
+#[derive(Debug, StructOpt)]
+#[structopt(about = "Publishes docker container, host and swarm state to mqtt")]
+pub struct Args {
+    /// Configuration file [default: /docker2mqtt/config/configuration.y(a)ml]
+    #[structopt(long, parse(from_os_str))]
+    pub config: Option<PathBuf>,
+
+    /// Directory for persisted state
+    #[structopt(long, parse(from_os_str), default_value = "/docker2mqtt/data")]
+    pub data_dir: PathBuf,
+
+    /// Directory for rolling log files
+    #[structopt(long, parse(from_os_str), default_value = "/docker2mqtt/logs")]
+    pub log_dir: PathBuf,
+
+    #[structopt(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Debug, PartialEq, StructOpt)]
+pub enum Command {
+    /// Validates the configuration file and exits
+    CheckConfig,
+
+    /// Queries the readiness endpoint of a running instance
+    Healthcheck,
+
+    /// Copies persisted state within the data directory from one backend (json, sled or sqlite) to another
+    Migrate {
+        #[structopt(long)]
+        from: Backend,
+
+        #[structopt(long)]
+        to: Backend,
+    },
+
+    /// Prints the effective configuration with secrets redacted
+    PrintConfig,
+
+    /// Prints the default configuration
+    PrintDefaultConfig,
+}
+
+#[cfg(test)]
+mod must {
+    use std::path::PathBuf;
+
+    use structopt::StructOpt;
+
+    use crate::configuration::Backend;
+
+    use super::{Args, Command};
+
+    #[test]
+    fn use_default_directories() {
+        // act
+        let args = Args::from_iter(vec!["docker2mqtt"]);
+
+        // assert
+        assert_eq!(None, args.config);
+        assert_eq!(PathBuf::from("/docker2mqtt/data"), args.data_dir);
+        assert_eq!(PathBuf::from("/docker2mqtt/logs"), args.log_dir);
+        assert_eq!(None, args.command);
+    }
+
+    #[test]
+    fn parse_options_and_subcommand() {
+        // act
+        let args = Args::from_iter(vec![
+            "docker2mqtt",
+            "--config",
+            "./configuration.yaml",
+            "--data-dir",
+            "./data",
+            "check-config",
+        ]);
+
+        // assert
+        assert_eq!(Some(PathBuf::from("./configuration.yaml")), args.config);
+        assert_eq!(PathBuf::from("./data"), args.data_dir);
+        assert_eq!(Some(Command::CheckConfig), args.command);
+    }
+
+    #[test]
+    fn parse_migrate_backends() {
+        // act
+        let args = Args::from_iter(vec![
+            "docker2mqtt",
+            "migrate",
+            "--from",
+            "sled",
+            "--to",
+            "sqlite",
+        ]);
+
+        // assert
+        assert_eq!(
+            Some(Command::Migrate {
+                from: Backend::Sled,
+                to: Backend::Sqlite
+            }),
+            args.command
+        );
+    }
+}
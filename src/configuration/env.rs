@@ -0,0 +1,253 @@
+use std::{fs, path::Path};
+
+use serde_yaml::{Mapping, Value};
+
+use super::ConfigurationErr;
+
+const PREFIX: &str = "D2M_";
+const SEPARATOR: &str = "__";
+const FILE_SUFFIX: &str = "_FILE";
+
+// applies D2M_ prefixed variables on top of the yaml and returns the count of applied variables
+pub fn apply(
+    root: &mut Value,
+    schema: &Value,
+    vars: impl Iterator<Item = (String, String)>,
+) -> Result<usize, ConfigurationErr> {
+    let mut vars: Vec<(String, String)> = vars.filter(|(key, _)| key.starts_with(PREFIX)).collect();
+
+    // plain variables win over their _FILE variant
+    vars.sort_by_key(|(key, _)| !key.ends_with(FILE_SUFFIX));
+
+    for (key, value) in vars.iter() {
+        let (path, file) = match key.strip_suffix(FILE_SUFFIX) {
+            Some(path) => (path, true),
+            None => (key.as_str(), false),
+        };
+
+        let path: Vec<String> = path[PREFIX.len()..]
+            .split(SEPARATOR)
+            .map(|segment| segment.to_ascii_lowercase())
+            .collect();
+
+        if path.iter().any(|segment| segment.is_empty()) {
+            return Err(ConfigurationErr::Env(
+                key.to_owned(),
+                "empty key segment".to_owned(),
+            ));
+        }
+
+        let value = match file {
+            true => read_secret(key, value)?,
+            false => parse_value(key, value, get_node(schema, &path))?,
+        };
+
+        insert(root, &path, value);
+    }
+
+    Ok(vars.len())
+}
+
+fn read_secret(key: &str, file: &str) -> Result<Value, ConfigurationErr> {
+    match fs::read_to_string(Path::new(file)) {
+        Ok(content) => Ok(Value::String(
+            content.trim_end_matches(['\n', '\r']).to_owned(),
+        )),
+        Err(e) => Err(ConfigurationErr::Env(
+            key.to_owned(),
+            format!("could not read {}: {}", file, e),
+        )),
+    }
+}
+
+// lists and mappings are yaml, scalars stay text unless the key holds a number or bool, thus
+// passwords like 0x1F or 1e3 are kept verbatim
+fn parse_value(key: &str, value: &str, schema: Option<&Value>) -> Result<Value, ConfigurationErr> {
+    let structured = value.starts_with('[') || value.starts_with('{');
+    let typed = matches!(schema, Some(Value::Bool(_)) | Some(Value::Number(_)));
+    if value.is_empty() || !(structured || typed) {
+        return Ok(Value::String(value.to_owned()));
+    }
+
+    serde_yaml::from_str(value).map_err(|e| ConfigurationErr::Env(key.to_owned(), e.to_string()))
+}
+
+fn get_node<'a>(schema: &'a Value, path: &[String]) -> Option<&'a Value> {
+    path.iter()
+        .try_fold(schema, |node, key| node.get(key.as_str()))
+}
+
+fn insert(node: &mut Value, path: &[String], value: Value) {
+    if !node.is_mapping() {
+        *node = Value::Mapping(Mapping::new());
+    }
+
+    let mapping = match node {
+        Value::Mapping(mapping) => mapping,
+        _ => unreachable!(),
+    };
+
+    let key = Value::String(path[0].to_owned());
+    if path.len() == 1 {
+        mapping.insert(key, value);
+        return;
+    }
+
+    if !mapping.contains_key(&key) {
+        mapping.insert(key.clone(), Value::Null);
+    }
+
+    if let Some(child) = mapping.get_mut(&key) {
+        insert(child, &path[1..], value);
+    }
+}
+
+#[cfg(test)]
+mod must {
+    use std::io::Write;
+
+    use serde_yaml::Value;
+
+    use super::apply;
+
+    fn schema() -> Value {
+        super::super::Configuration::schema()
+    }
+
+    fn vars(vars: &[(&str, &str)]) -> impl Iterator<Item = (String, String)> {
+        vars.iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect::<Vec<_>>()
+            .into_iter()
+    }
+
+    fn get<'a>(root: &'a Value, path: &[&str]) -> &'a Value {
+        path.iter().fold(root, |node, key| &node[*key])
+    }
+
+    #[test]
+    fn override_nested_keys() {
+        // arrange
+        let mut root: Value = serde_yaml::from_str("mqtt:\n  host: a\n  port: 1883").unwrap();
+
+        // act
+        let applied = apply(
+            &mut root,
+            &schema(),
+            vars(&[
+                ("D2M_MQTT__HOST", "broker"),
+                ("D2M_DOCKER__STREAM_LOGS_CONTAINER", "[borg, nginx]"),
+                ("HOME", "/root"),
+            ]),
+        )
+        .unwrap();
+
+        // assert
+        assert_eq!(2, applied);
+        assert_eq!(&Value::from("broker"), get(&root, &["mqtt", "host"]));
+        assert_eq!(&Value::from(1883), get(&root, &["mqtt", "port"]));
+        assert_eq!(
+            &Value::Sequence(vec![Value::from("borg"), Value::from("nginx")]),
+            get(&root, &["docker", "stream_logs_container"])
+        );
+    }
+
+    #[test]
+    fn create_missing_sections() {
+        // arrange
+        let mut root = Value::Null;
+
+        // act
+        apply(
+            &mut root,
+            &schema(),
+            vars(&[("D2M_HOST__PING_INTERVAL", "10")]),
+        )
+        .unwrap();
+
+        // assert
+        assert_eq!(&Value::from(10), get(&root, &["host", "ping_interval"]));
+    }
+
+    // concurrent runs and other users share the temp dir, thus the path names process and test
+    fn create_secret_path(name: &str) -> std::path::PathBuf {
+        std::env::temp_dir().join(format!("d2m_env_{}_{}", std::process::id(), name))
+    }
+
+    #[test]
+    fn read_secrets_from_files() {
+        // arrange
+        let path = create_secret_path("read_secrets_from_files");
+        let mut file = std::fs::File::create(&path).unwrap();
+        writeln!(file, "s3cret").unwrap();
+
+        let mut root = Value::Null;
+
+        // act
+        let result = apply(
+            &mut root,
+            &schema(),
+            vars(&[("D2M_MQTT__PASSWORD_FILE", path.to_str().unwrap())]),
+        );
+        let _ = std::fs::remove_file(&path);
+        result.unwrap();
+
+        // assert
+        assert_eq!(&Value::from("s3cret"), get(&root, &["mqtt", "password"]));
+    }
+
+    #[test]
+    fn prefer_plain_variable_over_file() {
+        // arrange
+        let path = create_secret_path("prefer_plain_variable_over_file");
+        std::fs::write(&path, "from_file").unwrap();
+
+        let mut root = Value::Null;
+
+        // act
+        let result = apply(
+            &mut root,
+            &schema(),
+            vars(&[
+                ("D2M_MQTT__PASSWORD", "plain"),
+                ("D2M_MQTT__PASSWORD_FILE", path.to_str().unwrap()),
+            ]),
+        );
+        let _ = std::fs::remove_file(&path);
+        result.unwrap();
+
+        // assert
+        assert_eq!(&Value::from("plain"), get(&root, &["mqtt", "password"]));
+    }
+
+    #[test]
+    fn keep_secrets_verbatim() {
+        // arrange
+        let mut root = Value::Null;
+
+        // act
+        apply(
+            &mut root,
+            &schema(),
+            vars(&[
+                ("D2M_MQTT__CLIENT_ID", "qwert"),
+                ("D2M_MQTT__HOST", "yuio"),
+                ("D2M_MQTT__PASSWORD", "0x1F"),
+                ("D2M_MQTT__PORT", "1883"),
+                ("D2M_MQTT__USERNAME", "null"),
+                ("D2M_UPDATES__PASSWORD", "1e3"),
+            ]),
+        )
+        .unwrap();
+
+        // assert
+        assert_eq!(&Value::from("0x1F"), get(&root, &["mqtt", "password"]));
+        assert_eq!(&Value::from(1883), get(&root, &["mqtt", "port"]));
+        assert_eq!(&Value::from("null"), get(&root, &["mqtt", "username"]));
+        assert_eq!(&Value::from("1e3"), get(&root, &["updates", "password"]));
+
+        let text = serde_yaml::to_string(&root).unwrap();
+        let conf: super::super::Configuration = serde_yaml::from_str(&text).unwrap();
+        assert_eq!(Some("0x1F".to_owned()), conf.mqtt.password);
+    }
+}
@@ -0,0 +1,1161 @@
+use std::{
+    collections::HashMap,
+    fmt,
+    fs::File,
+    io::{self, Read},
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+use serde::{Deserialize, Serialize};
+use serde_yaml::Value;
+use tracing::instrument;
+
+pub use self::validate::Problem;
+
+mod env;
+mod validate;
+
+const REDACTED: &str = "<redacted>";
+
+const DEFAULT_FILES: [&str; 2] = [
+    "/docker2mqtt/config/configuration.yaml",
+    "/docker2mqtt/config/configuration.yml",
+];
+
+#[derive(Debug)]
+pub enum ConfigurationErr {
+    Env(String, String),
+    Invalid(String, Vec<Problem>),
+    Missing(Vec<PathBuf>),
+    Parse(String, serde_yaml::Error),
+    Read(PathBuf, io::Error),
+}
+
+impl fmt::Display for ConfigurationErr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ConfigurationErr::Env(key, reason) => write!(f, "invalid {}: {}", key, reason),
+            ConfigurationErr::Invalid(source, problems) => {
+                write!(f, "invalid configuration in {}", source)?;
+                for problem in problems {
+                    write!(f, "\n  - {}", problem)?;
+                }
+
+                Ok(())
+            }
+            ConfigurationErr::Missing(paths) => {
+                write!(f, "configuration file missing, tried {:?}", paths)
+            }
+            ConfigurationErr::Parse(source, e) => write!(f, "invalid {}: {}", source, e),
+            ConfigurationErr::Read(path, e) => {
+                write!(f, "could not read {}: {}", path.display(), e)
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct Configuration {
+    #[serde(default)]
+    pub docker: Docker,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hassio: Option<Hassio>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub history: Option<History>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub host: Option<Host>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub http: Option<Http>,
+
+    #[serde(default)]
+    pub logging: Logging,
+
+    pub mqtt: Mqtt,
+
+    #[serde(default)]
+    pub shutdown: Shutdown,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub swarm: Option<Swarm>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub updates: Option<Updates>,
+}
+
+impl Configuration {
+    #[instrument]
+    pub fn load(path: Option<&Path>) -> Result<Configuration, ConfigurationErr> {
+        let file = match path {
+            Some(path) => Some((path.to_owned(), read_file(path)?)),
+            None => read_default_file()?,
+        };
+
+        let (source, mut root) = match &file {
+            Some((path, content)) if content.trim().is_empty() => {
+                (path.display().to_string(), Value::Null)
+            }
+            Some((path, content)) => (
+                path.display().to_string(),
+                serde_yaml::from_str(content)
+                    .map_err(|e| ConfigurationErr::Parse(path.display().to_string(), e))?,
+            ),
+            None => ("environment".to_owned(), Value::Null),
+        };
+
+        let applied = env::apply(&mut root, &Configuration::schema(), std::env::vars())?;
+        match (&file, applied) {
+            (None, 0) => Err(ConfigurationErr::Missing(
+                DEFAULT_FILES.iter().map(PathBuf::from).collect(),
+            )),
+            // the untouched file keeps line numbers of errors accurate
+            (Some((_, content)), 0) if !content.trim().is_empty() => parse(content, source),
+            _ => {
+                // round trip through text, so plain env values like 1234 deserialize into strings too
+                let content = serde_yaml::to_string(&root).unwrap();
+                parse(&content, source)
+            }
+        }
+    }
+
+    // files which get loaded for the given path, used to watch for changes
+    pub fn files(path: Option<&Path>) -> Vec<PathBuf> {
+        match path {
+            Some(path) => vec![path.to_owned()],
+            None => DEFAULT_FILES.iter().map(PathBuf::from).collect(),
+        }
+    }
+
+    pub fn redacted_yaml(&self) -> String {
+        let redact = |secret: &Option<String>| secret.as_ref().map(|_| REDACTED.to_owned());
+
+        let mut conf = self.clone();
+        conf.mqtt.password = redact(&conf.mqtt.password);
+        if let Some(updates) = &mut conf.updates {
+            updates.password = redact(&updates.password);
+        }
+
+        serde_yaml::to_string(&conf).unwrap()
+    }
+
+    // every section with its defaults, the types of the values tell how to read environment variables
+    fn schema() -> Value {
+        let conf = Configuration {
+            docker: Docker::default(),
+            hassio: Some(Hassio::default()),
+            history: Some(History::default()),
+            host: Some(Host::default()),
+            http: Some(Http::default()),
+            logging: Logging::default(),
+            mqtt: Mqtt::default(),
+            shutdown: Shutdown::default(),
+            swarm: Some(Swarm::default()),
+            updates: Some(Updates::default()),
+        };
+
+        serde_yaml::to_value(&conf).unwrap_or(Value::Null)
+    }
+
+    // optional sections are listed commented out with their defaults
+    pub fn default_yaml() -> String {
+        let conf = Configuration {
+            docker: Docker::default(),
+            hassio: None,
+            history: None,
+            host: None,
+            http: None,
+            logging: Logging::default(),
+            mqtt: Mqtt::default(),
+            shutdown: Shutdown::default(),
+            swarm: None,
+            updates: None,
+        };
+
+        let mut yaml = serde_yaml::to_string(&conf).unwrap();
+        append_commented(&mut yaml, "hassio", &Hassio::default());
+        append_commented(&mut yaml, "history", &History::default());
+        append_commented(&mut yaml, "host", &Host::default());
+        append_commented(&mut yaml, "http", &Http::default());
+        append_commented(&mut yaml, "swarm", &Swarm::default());
+        append_commented(&mut yaml, "updates", &Updates::default());
+
+        yaml
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct Docker {
+    #[serde(default)]
+    pub persist_backend: Backend,
+
+    #[serde(default)]
+    pub persist_state: bool,
+
+    #[serde(default)]
+    pub stats_aggregation: Aggregation,
+
+    #[serde(default)]
+    pub stats_container: HashMap<String, StatsContainer>,
+
+    #[serde(default)]
+    pub stats_deadband: f64,
+
+    #[serde(default)]
+    pub stats_interval: u64,
+
+    #[serde(default)]
+    pub stats_windows: Vec<u64>,
+
+    #[serde(default = "Docker::default_stream_logs")]
+    pub stream_logs: bool,
+
+    #[serde(default = "Docker::default_stream_logs_alert_timeout")]
+    pub stream_logs_alert_timeout: u64,
+
+    #[serde(default)]
+    pub stream_logs_alerts: HashMap<String, Vec<LogAlert>>,
+
+    #[serde(default = "Docker::default_stream_logs_burst")]
+    pub stream_logs_burst: u64,
+
+    #[serde(default)]
+    pub stream_logs_container: Vec<String>,
+
+    #[serde(default)]
+    pub stream_logs_filter: Vec<String>,
+
+    #[serde(default)]
+    pub stream_logs_multiline: HashMap<String, Multiline>,
+
+    #[serde(default)]
+    pub stream_logs_parser: HashMap<String, LogParser>,
+
+    #[serde(default)]
+    pub stream_logs_rate: f64,
+
+    #[serde(default)]
+    pub stream_logs_split: bool,
+
+    #[serde(default)]
+    pub track_networks: bool,
+
+    #[serde(default)]
+    pub track_uptime: bool,
+
+    #[serde(default)]
+    pub track_volumes: bool,
+}
+
+impl Default for Docker {
+    fn default() -> Self {
+        Docker {
+            persist_backend: Backend::default(),
+            persist_state: false,
+            stats_aggregation: Aggregation::default(),
+            stats_container: HashMap::new(),
+            stats_deadband: 0.0,
+            stats_interval: 0,
+            stats_windows: vec![],
+            stream_logs: true,
+            stream_logs_alert_timeout: Docker::default_stream_logs_alert_timeout(),
+            stream_logs_alerts: HashMap::new(),
+            stream_logs_burst: Docker::default_stream_logs_burst(),
+            stream_logs_container: vec![],
+            stream_logs_filter: vec![],
+            stream_logs_multiline: HashMap::new(),
+            stream_logs_parser: HashMap::new(),
+            stream_logs_rate: 0.0,
+            stream_logs_split: false,
+            track_networks: false,
+            track_uptime: false,
+            track_volumes: false,
+        }
+    }
+}
+
+impl Docker {
+    fn default_stream_logs() -> bool {
+        true
+    }
+
+    fn default_stream_logs_alert_timeout() -> u64 {
+        300
+    }
+
+    fn default_stream_logs_burst() -> u64 {
+        50
+    }
+
+    pub fn get_log_settings(&self, container_name: &str) -> LogSettings {
+        LogSettings {
+            alert_timeout: self.stream_logs_alert_timeout,
+            alerts: self.stream_logs_alerts.get(container_name).cloned(),
+            burst: self.stream_logs_burst,
+            enabled: self.stream_logs,
+            filter: self.stream_logs_filter.clone(),
+            multiline: self.stream_logs_multiline.get(container_name).cloned(),
+            parser: self.stream_logs_parser.get(container_name).cloned(),
+            publish: self.publishes_logs(container_name),
+            rate: self.stream_logs_rate,
+            split: self.stream_logs_split,
+        }
+    }
+
+    pub fn publishes_logs(&self, container_name: &str) -> bool {
+        self.stream_logs_container
+            .iter()
+            .any(|name| name.eq_ignore_ascii_case(container_name))
+    }
+
+    pub fn get_stats_settings(&self, container_name: &str) -> StatsSettings {
+        let container = self.stats_container.get(container_name);
+
+        StatsSettings {
+            aggregation: container
+                .and_then(|c| c.aggregation)
+                .unwrap_or(self.stats_aggregation),
+            deadband: container
+                .and_then(|c| c.deadband)
+                .unwrap_or(self.stats_deadband),
+            interval: container
+                .and_then(|c| c.interval)
+                .unwrap_or(self.stats_interval),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Aggregation {
+    #[default]
+    Average,
+    Max,
+}
+
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Backend {
+    Json,
+    #[default]
+    Sled,
+    Sqlite,
+}
+
+impl fmt::Display for Backend {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let value = match self {
+            Backend::Json => "json",
+            Backend::Sled => "sled",
+            Backend::Sqlite => "sqlite",
+        };
+
+        write!(f, "{}", value)
+    }
+}
+
+impl FromStr for Backend {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "json" => Ok(Backend::Json),
+            "sled" => Ok(Backend::Sled),
+            "sqlite" => Ok(Backend::Sqlite),
+            _ => Err(format!(
+                "unknown backend {}, use json, sled or sqlite",
+                value
+            )),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct StatsContainer {
+    #[serde(default)]
+    pub aggregation: Option<Aggregation>,
+
+    #[serde(default)]
+    pub deadband: Option<f64>,
+
+    #[serde(default)]
+    pub interval: Option<u64>,
+}
+
+// everything a log stream binds on start
+#[derive(Clone, Debug, PartialEq)]
+pub struct LogSettings {
+    pub alert_timeout: u64,
+    pub alerts: Option<Vec<LogAlert>>,
+    pub burst: u64,
+    pub enabled: bool,
+    pub filter: Vec<String>,
+    pub multiline: Option<Multiline>,
+    pub parser: Option<LogParser>,
+    pub publish: bool,
+    pub rate: f64,
+    pub split: bool,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct StatsSettings {
+    pub aggregation: Aggregation,
+    pub deadband: f64,
+    pub interval: u64,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct LogAlert {
+    pub name: String,
+    pub pattern: String,
+
+    #[serde(default = "LogAlert::default_severity")]
+    pub severity: String,
+}
+
+impl LogAlert {
+    fn default_severity() -> String {
+        "warning".to_owned()
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct Multiline {
+    #[serde(default)]
+    pub continuation: Option<String>,
+
+    #[serde(default)]
+    pub start: Option<String>,
+
+    #[serde(default = "Multiline::default_timeout")]
+    pub timeout: u64,
+}
+
+impl Multiline {
+    fn default_timeout() -> u64 {
+        1000
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct LogParser {
+    pub format: LogFormat,
+
+    #[serde(default)]
+    pub level: Option<LogLevel>,
+
+    #[serde(default)]
+    pub pattern: Option<String>,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogFormat {
+    Json,
+    Logfmt,
+    Regex,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Ord, PartialEq, PartialOrd, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogLevel {
+    Trace,
+    Debug,
+    Info,
+    Warn,
+    Error,
+    Fatal,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct Hassio {
+    pub discovery: bool,
+
+    #[serde(default = "Hassio::default_discovery_prefix")]
+    pub discovery_prefix: String,
+
+    #[serde(default = "Hassio::default_device_prefix")]
+    pub device_prefix: String,
+}
+
+impl Default for Hassio {
+    fn default() -> Self {
+        Hassio {
+            discovery: true,
+            discovery_prefix: Hassio::default_discovery_prefix(),
+            device_prefix: Hassio::default_device_prefix(),
+        }
+    }
+}
+
+impl Hassio {
+    fn default_discovery_prefix() -> String {
+        "homeassistant".to_owned()
+    }
+
+    fn default_device_prefix() -> String {
+        "docker".to_owned()
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct History {
+    #[serde(default = "History::default_keep_removed")]
+    pub keep_removed: u64,
+
+    #[serde(default = "History::default_limit")]
+    pub limit: usize,
+}
+
+impl Default for History {
+    fn default() -> Self {
+        History {
+            keep_removed: History::default_keep_removed(),
+            limit: History::default_limit(),
+        }
+    }
+}
+
+impl History {
+    fn default_keep_removed() -> u64 {
+        30 * 24 * 60 * 60
+    }
+
+    fn default_limit() -> usize {
+        100
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct Host {
+    #[serde(default = "Host::default_inventory_interval")]
+    pub inventory_interval: u64,
+
+    #[serde(default = "Host::default_ping_interval")]
+    pub ping_interval: u64,
+}
+
+impl Default for Host {
+    fn default() -> Self {
+        Host {
+            inventory_interval: Host::default_inventory_interval(),
+            ping_interval: Host::default_ping_interval(),
+        }
+    }
+}
+
+impl Host {
+    fn default_inventory_interval() -> u64 {
+        300
+    }
+
+    fn default_ping_interval() -> u64 {
+        30
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct Http {
+    #[serde(default = "Http::default_address")]
+    pub address: String,
+}
+
+impl Default for Http {
+    fn default() -> Self {
+        Http {
+            address: Http::default_address(),
+        }
+    }
+}
+
+impl Http {
+    fn default_address() -> String {
+        "0.0.0.0:9184".to_owned()
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct Logging {
+    #[serde(default = "Logging::default_level")]
+    pub level: String,
+}
+
+impl Default for Logging {
+    fn default() -> Self {
+        Logging {
+            level: Logging::default_level(),
+        }
+    }
+}
+
+impl Logging {
+    fn default_level() -> String {
+        "INFO".to_owned()
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct Mqtt {
+    pub client_id: String,
+    pub host: String,
+    pub port: u16,
+
+    #[serde(default = "Mqtt::default_connection_timeout")]
+    pub connection_timeout: u64,
+
+    #[serde(default = "Mqtt::default_keep_alive")]
+    pub keep_alive: u16,
+
+    #[serde(default)]
+    pub password: Option<String>,
+
+    #[serde(default = "Mqtt::default_qos")]
+    pub qos: u8,
+
+    #[serde(default)]
+    pub username: Option<String>,
+}
+
+impl Default for Mqtt {
+    fn default() -> Self {
+        Mqtt {
+            client_id: "docker2mqtt".to_owned(),
+            host: "localhost".to_owned(),
+            port: 1883,
+            connection_timeout: Mqtt::default_connection_timeout(),
+            keep_alive: Mqtt::default_keep_alive(),
+            password: None,
+            qos: Mqtt::default_qos(),
+            username: None,
+        }
+    }
+}
+
+impl Mqtt {
+    fn default_connection_timeout() -> u64 {
+        20
+    }
+
+    fn default_keep_alive() -> u16 {
+        30
+    }
+
+    fn default_qos() -> u8 {
+        0
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct Shutdown {
+    #[serde(default = "Shutdown::default_timeout")]
+    pub timeout: u64,
+}
+
+impl Default for Shutdown {
+    fn default() -> Self {
+        Shutdown {
+            timeout: Shutdown::default_timeout(),
+        }
+    }
+}
+
+impl Shutdown {
+    fn default_timeout() -> u64 {
+        5
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct Swarm {
+    #[serde(default = "Swarm::default_interval")]
+    pub interval: u64,
+}
+
+impl Default for Swarm {
+    fn default() -> Self {
+        Swarm {
+            interval: Swarm::default_interval(),
+        }
+    }
+}
+
+impl Swarm {
+    fn default_interval() -> u64 {
+        10
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct Updates {
+    #[serde(default = "Updates::default_interval")]
+    pub interval: u64,
+
+    #[serde(default)]
+    pub password: Option<String>,
+
+    #[serde(default = "Updates::default_registry")]
+    pub registry: String,
+
+    #[serde(default)]
+    pub username: Option<String>,
+}
+
+impl Default for Updates {
+    fn default() -> Self {
+        Updates {
+            interval: Updates::default_interval(),
+            password: None,
+            registry: Updates::default_registry(),
+            username: None,
+        }
+    }
+}
+
+impl Updates {
+    fn default_interval() -> u64 {
+        3600
+    }
+
+    fn default_registry() -> String {
+        "https://registry-1.docker.io".to_owned()
+    }
+}
+
+fn parse(content: &str, source: String) -> Result<Configuration, ConfigurationErr> {
+    let mut problems = vec![];
+
+    let mut unknown = |path: serde_ignored::Path| {
+        problems.push(Problem::new(get_path(&path), "unknown key"));
+    };
+
+    let deserializer =
+        serde_ignored::Deserializer::new(serde_yaml::Deserializer::from_str(content), &mut unknown);
+    let result: Result<Configuration, _> = serde_path_to_error::deserialize(deserializer);
+
+    match result {
+        Ok(conf) => {
+            problems.extend(validate::validate(&conf));
+            match problems.is_empty() {
+                true => Ok(conf),
+                false => Err(ConfigurationErr::Invalid(source, problems)),
+            }
+        }
+        Err(e) => {
+            let path = e.path().to_string();
+
+            // serde_yaml prefixes nested errors with the path already
+            let reason = e.inner().to_string();
+            let reason = reason
+                .strip_prefix(&format!("{}: ", path))
+                .unwrap_or(&reason)
+                .to_owned();
+
+            problems.push(Problem::new(path, reason));
+            Err(ConfigurationErr::Invalid(source, problems))
+        }
+    }
+}
+
+fn get_path(path: &serde_ignored::Path) -> String {
+    match path {
+        serde_ignored::Path::Root => "".to_owned(),
+        serde_ignored::Path::Seq { parent, index } => format!("{}[{}]", get_path(parent), index),
+        serde_ignored::Path::Map { parent, key } => match get_path(parent).as_str() {
+            "" => key.to_owned(),
+            parent => format!("{}.{}", parent, key),
+        },
+        serde_ignored::Path::Some { parent }
+        | serde_ignored::Path::NewtypeStruct { parent }
+        | serde_ignored::Path::NewtypeVariant { parent } => get_path(parent),
+    }
+}
+
+fn read_default_file() -> Result<Option<(PathBuf, String)>, ConfigurationErr> {
+    for file in DEFAULT_FILES.iter() {
+        let path = PathBuf::from(file);
+        match read_file(&path) {
+            Ok(content) => return Ok(Some((path, content))),
+            Err(ConfigurationErr::Read(_, e)) if e.kind() == io::ErrorKind::NotFound => {}
+            Err(e) => return Err(e),
+        }
+    }
+
+    // configuration may be given by environment only
+    Ok(None)
+}
+
+fn read_file(path: &Path) -> Result<String, ConfigurationErr> {
+    let read = |path: &Path| -> io::Result<String> {
+        let mut file = File::open(path)?;
+        let mut content = String::new();
+
+        file.read_to_string(&mut content)?;
+
+        Ok(content)
+    };
+
+    read(path).map_err(|e| ConfigurationErr::Read(path.to_owned(), e))
+}
+
+fn append_commented<T: Serialize>(yaml: &mut String, name: &str, section: &T) {
+    let section = serde_yaml::to_string(section).unwrap();
+
+    yaml.push_str(&format!("\n# {}:\n", name));
+    for line in section.lines().filter(|line| *line != "---") {
+        yaml.push_str(&format!("#   {}\n", line));
+    }
+}
+
+#[cfg(test)]
+mod must {
+    #[test]
+    fn parse_defaults_for_minimal_config() {
+        // arrange
+        let buffer = "
+mqtt:
+  client_id: qwert
+  host: yuio
+  port: 1234";
+
+        // act
+        let mut config: super::Configuration = serde_yaml::from_str(buffer).unwrap();
+
+        // assert
+        assert!(config.hassio.is_none());
+        assert!(config.host.is_none());
+
+        assert_eq!(super::Backend::Sled, config.docker.persist_backend);
+        assert!(!config.docker.persist_state);
+        assert!(config.docker.stream_logs);
+        assert_eq!(config.docker.stream_logs_container.pop(), None);
+        assert_eq!(config.docker.stream_logs_filter.pop(), None);
+        assert!(!config.docker.track_networks);
+        assert!(!config.docker.track_uptime);
+        assert!(!config.docker.track_volumes);
+
+        assert_eq!("INFO", config.logging.level);
+
+        assert_eq!(20, config.mqtt.connection_timeout);
+        assert_eq!(30, config.mqtt.keep_alive);
+        assert_eq!(0, config.mqtt.qos);
+
+        assert_eq!(5, config.shutdown.timeout);
+
+        assert!(config.http.is_none());
+        assert!(config.swarm.is_none());
+        assert!(config.updates.is_none());
+    }
+
+    #[test]
+    fn parse_given_values_for_config() {
+        // arrange
+        let buffer = "
+docker:
+  persist_backend: sqlite
+  persist_state: true
+  stream_logs_container:
+    - docker2mqtt
+    - borg
+  stream_logs_filter:
+    - test
+    - test02
+
+mqtt:
+  client_id: qwert
+  host: yuio
+  port: 1234";
+
+        // act
+        let mut config: super::Configuration = serde_yaml::from_str(buffer).unwrap();
+
+        // assert
+        assert_eq!(super::Backend::Sqlite, config.docker.persist_backend);
+        assert!(config.docker.persist_state);
+        assert!(config.docker.stream_logs);
+
+        assert_eq!(
+            config.docker.stream_logs_container.pop(),
+            Some("borg".to_owned())
+        );
+        assert_eq!(
+            config.docker.stream_logs_container.pop(),
+            Some("docker2mqtt".to_owned())
+        );
+        assert_eq!(config.docker.stream_logs_container.pop(), None);
+
+        assert_eq!(
+            config.docker.stream_logs_filter.pop(),
+            Some("test02".to_owned())
+        );
+        assert_eq!(
+            config.docker.stream_logs_filter.pop(),
+            Some("test".to_owned())
+        );
+        assert_eq!(config.docker.stream_logs_filter.pop(), None);
+    }
+
+    #[test]
+    fn parse_defaults_for_updates() {
+        // arrange
+        let buffer = "
+mqtt:
+  client_id: qwert
+  host: yuio
+  port: 1234
+
+updates:
+  username: user";
+
+        // act
+        let config: super::Configuration = serde_yaml::from_str(buffer).unwrap();
+
+        // assert
+        let updates = config.updates.unwrap();
+
+        assert_eq!(3600, updates.interval);
+        assert_eq!("https://registry-1.docker.io", updates.registry);
+        assert_eq!(Some("user".to_owned()), updates.username);
+        assert_eq!(None, updates.password);
+    }
+
+    #[test]
+    fn parse_defaults_for_host() {
+        // arrange
+        let buffer = "
+host: {}
+
+mqtt:
+  client_id: qwert
+  host: yuio
+  port: 1234";
+
+        // act
+        let config: super::Configuration = serde_yaml::from_str(buffer).unwrap();
+
+        // assert
+        let host = config.host.unwrap();
+
+        assert_eq!(300, host.inventory_interval);
+        assert_eq!(30, host.ping_interval);
+    }
+
+    #[test]
+    fn parse_defaults_for_swarm() {
+        // arrange
+        let buffer = "
+mqtt:
+  client_id: qwert
+  host: yuio
+  port: 1234
+
+swarm: {}";
+
+        // act
+        let config: super::Configuration = serde_yaml::from_str(buffer).unwrap();
+
+        // assert
+        assert_eq!(10, config.swarm.unwrap().interval);
+    }
+
+    #[test]
+    fn parse_defaults_for_http() {
+        // arrange
+        let buffer = "
+http: {}
+
+mqtt:
+  client_id: qwert
+  host: yuio
+  port: 1234";
+
+        // act
+        let config: super::Configuration = serde_yaml::from_str(buffer).unwrap();
+
+        // assert
+        assert_eq!("0.0.0.0:9184", config.http.unwrap().address);
+    }
+
+    #[test]
+    fn resolve_stats_settings_per_container() {
+        // arrange
+        let buffer = "
+docker:
+  stats_aggregation: max
+  stats_deadband: 0.5
+  stats_interval: 30
+  stats_container:
+    nginx:
+      aggregation: average
+      interval: 5
+
+mqtt:
+  client_id: qwert
+  host: yuio
+  port: 1234";
+
+        // act
+        let config: super::Configuration = serde_yaml::from_str(buffer).unwrap();
+
+        // assert
+        assert_eq!(
+            super::StatsSettings {
+                aggregation: super::Aggregation::Average,
+                deadband: 0.5,
+                interval: 5,
+            },
+            config.docker.get_stats_settings("nginx")
+        );
+        assert_eq!(
+            super::StatsSettings {
+                aggregation: super::Aggregation::Max,
+                deadband: 0.5,
+                interval: 30,
+            },
+            config.docker.get_stats_settings("redis")
+        );
+    }
+
+    #[test]
+    fn parse_log_parser_per_container() {
+        // arrange
+        let buffer = "
+docker:
+  stream_logs_parser:
+    api:
+      format: json
+      level: warn
+    proxy:
+      format: regex
+      pattern: '(?P<level>\\w+) (?P<message>.*)'
+
+mqtt:
+  client_id: qwert
+  host: yuio
+  port: 1234";
+
+        // act
+        let config: super::Configuration = serde_yaml::from_str(buffer).unwrap();
+
+        // assert
+        let parsers = config.docker.stream_logs_parser;
+        assert_eq!(
+            Some(&super::LogParser {
+                format: super::LogFormat::Json,
+                level: Some(super::LogLevel::Warn),
+                pattern: None,
+            }),
+            parsers.get("api")
+        );
+        assert_eq!(super::LogFormat::Regex, parsers["proxy"].format);
+        assert!(super::LogLevel::Error > super::LogLevel::Warn);
+    }
+
+    #[test]
+    fn parse_default_yaml() {
+        // act
+        let config: super::Configuration =
+            serde_yaml::from_str(&super::Configuration::default_yaml()).unwrap();
+
+        // assert
+        assert_eq!("localhost", config.mqtt.host);
+        assert!(config.host.is_none());
+        assert_eq!(50, config.docker.stream_logs_burst);
+    }
+
+    #[test]
+    fn fail_loading_missing_file() {
+        // act
+        let result = super::Configuration::load(Some(std::path::Path::new(
+            "/nonexistent/configuration.yaml",
+        )));
+
+        // assert
+        assert!(matches!(result, Err(super::ConfigurationErr::Read(_, _))));
+    }
+
+    #[test]
+    fn redact_secrets() {
+        // arrange
+        let buffer = "
+mqtt:
+  client_id: qwert
+  host: yuio
+  port: 1234
+  password: s3cret
+
+updates:
+  password: t0ken";
+
+        let config: super::Configuration = serde_yaml::from_str(buffer).unwrap();
+
+        // act
+        let yaml = config.redacted_yaml();
+
+        // assert
+        assert!(!yaml.contains("s3cret"));
+        assert!(!yaml.contains("t0ken"));
+        assert_eq!(2, yaml.matches("<redacted>").count());
+    }
+
+    #[test]
+    fn report_unknown_keys_and_invalid_values() {
+        // arrange
+        let buffer = "
+docker:
+  persist_sate: true
+
+hassio:
+  discovery: true
+  discovry_prefix: ha
+
+mqtt:
+  client_id: qwert
+  host: yuio
+  port: 1234
+  qos: 3";
+
+        // act
+        let result = super::parse(buffer, "test".to_owned());
+
+        // assert
+        match result {
+            Err(super::ConfigurationErr::Invalid(source, problems)) => {
+                assert_eq!("test", source);
+                assert_eq!(
+                    vec![
+                        super::Problem::new("docker.persist_sate", "unknown key"),
+                        super::Problem::new("hassio.discovry_prefix", "unknown key"),
+                        super::Problem::new("mqtt.qos", "must be 0, 1 or 2"),
+                    ],
+                    problems
+                );
+            }
+            _ => panic!("expected invalid configuration"),
+        }
+    }
+
+    #[test]
+    fn report_path_of_type_errors() {
+        // arrange
+        let buffer = "
+mqtt:
+  client_id: qwert
+  host: yuio
+  port: abc";
+
+        // act
+        let result = super::parse(buffer, "test".to_owned());
+
+        // assert
+        match result {
+            Err(super::ConfigurationErr::Invalid(_, problems)) => {
+                assert_eq!("mqtt.port", problems[0].path);
+                assert!(problems[0].reason.starts_with("invalid type"));
+            }
+            _ => panic!("expected invalid configuration"),
+        }
+    }
+}
@@ -0,0 +1,316 @@
+use std::{collections::HashMap, fmt, net::SocketAddr};
+
+use regex::Regex;
+
+use super::{Configuration, LogFormat};
+
+#[derive(Debug, PartialEq)]
+pub struct Problem {
+    pub path: String,
+    pub reason: String,
+}
+
+impl Problem {
+    pub fn new(path: impl Into<String>, reason: impl Into<String>) -> Problem {
+        Problem {
+            path: path.into(),
+            reason: reason.into(),
+        }
+    }
+}
+
+impl fmt::Display for Problem {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.path.as_str() {
+            "" | "." => write!(f, "{}", self.reason),
+            path => write!(f, "{}: {}", path, self.reason),
+        }
+    }
+}
+
+pub fn validate(conf: &Configuration) -> Vec<Problem> {
+    let mut problems = vec![];
+
+    validate_docker(conf, &mut problems);
+
+    if let Some(history) = &conf.history {
+        if history.limit == 0 {
+            problems.push(Problem::new("history.limit", "must be greater than 0"));
+        }
+
+        if !conf.docker.persist_state {
+            problems.push(Problem::new("history", "requires docker.persist_state"));
+        }
+    }
+
+    if let Some(host) = &conf.host {
+        positive(
+            &mut problems,
+            "host.inventory_interval",
+            host.inventory_interval,
+        );
+        positive(&mut problems, "host.ping_interval", host.ping_interval);
+    }
+
+    if let Some(http) = &conf.http {
+        if let Err(e) = http.address.parse::<SocketAddr>() {
+            problems.push(Problem::new("http.address", e.to_string()));
+        }
+    }
+
+    if conf.mqtt.port == 0 {
+        problems.push(Problem::new("mqtt.port", "must not be 0"));
+    }
+
+    if conf.mqtt.qos > 2 {
+        problems.push(Problem::new("mqtt.qos", "must be 0, 1 or 2"));
+    }
+
+    positive(&mut problems, "shutdown.timeout", conf.shutdown.timeout);
+
+    if let Some(swarm) = &conf.swarm {
+        positive(&mut problems, "swarm.interval", swarm.interval);
+    }
+
+    if let Some(updates) = &conf.updates {
+        positive(&mut problems, "updates.interval", updates.interval);
+    }
+
+    problems
+}
+
+fn validate_docker(conf: &Configuration, problems: &mut Vec<Problem>) {
+    let docker = &conf.docker;
+
+    if docker.stats_deadband < 0.0 {
+        problems.push(Problem::new(
+            "docker.stats_deadband",
+            "must not be negative",
+        ));
+    }
+
+    for (index, window) in docker.stats_windows.iter().enumerate() {
+        positive(
+            problems,
+            &format!("docker.stats_windows[{}]", index),
+            *window,
+        );
+    }
+
+    for (container, settings) in sorted(&docker.stats_container) {
+        if settings.deadband.is_some_and(|deadband| deadband < 0.0) {
+            problems.push(Problem::new(
+                format!("docker.stats_container.{}.deadband", container),
+                "must not be negative",
+            ));
+        }
+    }
+
+    for (container, alerts) in sorted(&docker.stream_logs_alerts) {
+        for (index, alert) in alerts.iter().enumerate() {
+            let path = format!("docker.stream_logs_alerts.{}[{}].pattern", container, index);
+            regex(problems, &path, &alert.pattern);
+        }
+    }
+
+    for (index, filter) in docker.stream_logs_filter.iter().enumerate() {
+        regex(
+            problems,
+            &format!("docker.stream_logs_filter[{}]", index),
+            filter,
+        );
+    }
+
+    for (container, multiline) in sorted(&docker.stream_logs_multiline) {
+        let path = format!("docker.stream_logs_multiline.{}", container);
+        if multiline.start.is_none() && multiline.continuation.is_none() {
+            problems.push(Problem::new(&path, "requires start or continuation"));
+        }
+
+        if let Some(start) = &multiline.start {
+            regex(problems, &format!("{}.start", path), start);
+        }
+
+        if let Some(continuation) = &multiline.continuation {
+            regex(problems, &format!("{}.continuation", path), continuation);
+        }
+    }
+
+    for (container, parser) in sorted(&docker.stream_logs_parser) {
+        let path = format!("docker.stream_logs_parser.{}.pattern", container);
+        match (parser.format, &parser.pattern) {
+            (LogFormat::Regex, Some(pattern)) => regex(problems, &path, pattern),
+            (LogFormat::Regex, None) => problems.push(Problem::new(path, "required for regex")),
+            _ => {}
+        }
+    }
+
+    if docker.stream_logs_rate < 0.0 {
+        problems.push(Problem::new(
+            "docker.stream_logs_rate",
+            "must not be negative",
+        ));
+    }
+}
+
+// problems are reported in the same order on every run
+fn sorted<T>(map: &HashMap<String, T>) -> Vec<(&String, &T)> {
+    let mut entries: Vec<(&String, &T)> = map.iter().collect();
+    entries.sort_by(|a, b| a.0.cmp(b.0));
+
+    entries
+}
+
+fn positive(problems: &mut Vec<Problem>, path: &str, value: u64) {
+    if value == 0 {
+        problems.push(Problem::new(path, "must be greater than 0"));
+    }
+}
+
+fn regex(problems: &mut Vec<Problem>, path: &str, pattern: &str) {
+    if let Err(e) = Regex::new(pattern) {
+        // regex errors span multiple lines with a pointer to the position
+        let message = e.to_string();
+        let reason = message.lines().last().unwrap_or_default().trim();
+        let reason = reason.strip_prefix("error: ").unwrap_or(reason);
+
+        problems.push(Problem::new(path, format!("invalid regex, {}", reason)));
+    }
+}
+
+#[cfg(test)]
+mod must {
+    use super::{validate, Problem};
+
+    fn parse(buffer: &str) -> super::Configuration {
+        serde_yaml::from_str(buffer).unwrap()
+    }
+
+    #[test]
+    fn report_problems_of_containers_in_order() {
+        // arrange
+        let conf = parse(
+            "
+docker:
+  stream_logs_parser:
+    nginx:
+      format: regex
+    api:
+      format: regex
+    borg:
+      format: regex
+
+mqtt:
+  client_id: qwert
+  host: yuio
+  port: 1234",
+        );
+
+        // act
+        let problems = validate(&conf);
+
+        // assert
+        let paths: Vec<&str> = problems.iter().map(|p| p.path.as_str()).collect();
+        assert_eq!(
+            vec![
+                "docker.stream_logs_parser.api.pattern",
+                "docker.stream_logs_parser.borg.pattern",
+                "docker.stream_logs_parser.nginx.pattern",
+            ],
+            paths
+        );
+    }
+
+    #[test]
+    fn accept_minimal_config() {
+        // arrange
+        let conf = parse(
+            "
+mqtt:
+  client_id: qwert
+  host: yuio
+  port: 1234",
+        );
+
+        // act
+        let problems = validate(&conf);
+
+        // assert
+        assert!(problems.is_empty());
+    }
+
+    #[test]
+    fn collect_every_problem() {
+        // arrange
+        let conf = parse(
+            "
+docker:
+  stream_logs_filter:
+    - error
+    - '('
+  stream_logs_parser:
+    api:
+      format: regex
+
+host:
+  ping_interval: 0
+
+mqtt:
+  client_id: qwert
+  host: yuio
+  port: 1234
+  qos: 3",
+        );
+
+        // act
+        let problems = validate(&conf);
+
+        // assert
+        let paths: Vec<&str> = problems.iter().map(|p| p.path.as_str()).collect();
+        assert_eq!(
+            vec![
+                "docker.stream_logs_filter[1]",
+                "docker.stream_logs_parser.api.pattern",
+                "host.ping_interval",
+                "mqtt.qos",
+            ],
+            paths
+        );
+    }
+
+    #[test]
+    fn require_persisted_state_for_history() {
+        // arrange
+        let conf = parse(
+            "
+history:
+  limit: 0
+
+mqtt:
+  client_id: qwert
+  host: yuio
+  port: 1234",
+        );
+
+        // act
+        let problems = validate(&conf);
+
+        // assert
+        assert_eq!(
+            vec![
+                Problem::new("history.limit", "must be greater than 0"),
+                Problem::new("history", "requires docker.persist_state"),
+            ],
+            problems
+        );
+    }
+
+    #[test]
+    fn format_problem_with_path() {
+        // act
+        let problem = Problem::new("mqtt.qos", "must be 0, 1 or 2");
+
+        // assert
+        assert_eq!("mqtt.qos: must be 0, 1 or 2", problem.to_string());
+    }
+}
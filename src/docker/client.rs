@@ -1,9 +1,14 @@
-use bollard::Docker;
+use std::env;
+
+use bollard::{Docker, API_DEFAULT_VERSION};
 use tracing::{error, instrument};
 
+const DEFAULT_SOCKET: &str = "/var/run/docker.sock";
+const TIMEOUT: u64 = 120;
+
 #[instrument(level = "debug")]
 pub fn new() -> Docker {
-    match Docker::connect_with_unix_defaults() {
+    match Docker::connect_with_unix(&get_socket(), TIMEOUT, API_DEFAULT_VERSION) {
         Ok(client) => client,
         Err(e) => {
             error!("failed to create docker client: {}", e);
@@ -11,3 +16,33 @@ pub fn new() -> Docker {
         }
     }
 }
+
+// the docker cli honours DOCKER_HOST, thus both the client and the swarm requests do as well
+pub fn get_socket() -> String {
+    get_socket_path(env::var("DOCKER_HOST").ok().as_deref())
+}
+
+fn get_socket_path(docker_host: Option<&str>) -> String {
+    match docker_host.and_then(|host| host.strip_prefix("unix://")) {
+        Some(path) if !path.is_empty() => path.to_owned(),
+        _ => DEFAULT_SOCKET.to_owned(),
+    }
+}
+
+#[cfg(test)]
+mod must {
+    use super::get_socket_path;
+
+    #[test]
+    fn read_socket_path_of_docker_host() {
+        // act
+        let configured = get_socket_path(Some("unix:///run/user/1000/docker.sock"));
+        let remote = get_socket_path(Some("tcp://localhost:2375"));
+        let unset = get_socket_path(None);
+
+        // assert
+        assert_eq!("/run/user/1000/docker.sock", configured);
+        assert_eq!("/var/run/docker.sock", remote);
+        assert_eq!("/var/run/docker.sock", unset);
+    }
+}
@@ -1,7 +1,15 @@
 use std::collections::HashMap;
 
-use bollard::{container::ListContainersOptions, models::ContainerSummaryInner, Docker};
-use tracing::error;
+use bollard::{
+    container::ListContainersOptions,
+    models::{ContainerInspectResponse, ContainerSummaryInner},
+    Docker,
+};
+use tracing::{error, warn};
+
+use crate::events::{Event, EventType};
+
+const SERVICE_LABEL: &str = "com.docker.swarm.service.name";
 
 pub async fn get(client: &Docker) -> Vec<ContainerSummaryInner> {
     let filter = Some(ListContainersOptions::<String> {
@@ -52,6 +60,67 @@ pub fn get_name(container: &ContainerSummaryInner) -> &str {
     }
 }
 
+pub fn is_task(labels: Option<&HashMap<String, String>>) -> bool {
+    labels.is_some_and(|labels| labels.contains_key(SERVICE_LABEL))
+}
+
+pub async fn inspect(client: &Docker, id: &str) -> Option<ContainerInspectResponse> {
+    match client.inspect_container(id, None).await {
+        Ok(container) => Some(container),
+        Err(e) => {
+            warn!("could not inspect container {}: {}", id, e);
+            None
+        }
+    }
+}
+
+pub fn get_attachment_events(
+    container: &ContainerInspectResponse,
+    networks: bool,
+    volumes: bool,
+) -> Vec<Event> {
+    let container_name = container
+        .name
+        .as_deref()
+        .unwrap_or_default()
+        .trim_start_matches('/');
+
+    let mut events = vec![];
+    if networks {
+        let mut names: Vec<String> = container
+            .network_settings
+            .as_ref()
+            .and_then(|settings| settings.networks.as_ref())
+            .map(|networks| networks.keys().map(|name| name.to_owned()).collect())
+            .unwrap_or_default();
+        names.sort();
+
+        events.push(Event {
+            container_name: container_name.to_owned(),
+            event: EventType::Networks(names),
+        });
+    }
+
+    if volumes {
+        let mut names: Vec<String> = container
+            .mounts
+            .as_deref()
+            .unwrap_or_default()
+            .iter()
+            .filter(|mount| mount.typ.as_deref() == Some("volume"))
+            .filter_map(|mount| mount.name.to_owned())
+            .collect();
+        names.sort();
+
+        events.push(Event {
+            container_name: container_name.to_owned(),
+            event: EventType::Volumes(names),
+        });
+    }
+
+    events
+}
+
 fn split_first_char_remainder(s: &str) -> (&str, &str) {
     match s.chars().next() {
         Some(c) => s.split_at(c.len_utf8()),
@@ -0,0 +1,82 @@
+use bollard::{models::SystemEventsResponse, Docker};
+
+use crate::{docker::container, events::Event};
+
+use super::transition;
+
+pub async fn to_events(
+    client: &Docker,
+    response: SystemEventsResponse,
+    skip_tasks: bool,
+) -> Option<Vec<Event>> {
+    let container_id = transition::get_attribute(&response.actor, "container");
+    if container_id.is_empty() || !is_attachment(&response) {
+        return None;
+    }
+
+    let container = container::inspect(client, &container_id).await?;
+    let labels = container.config.as_ref().and_then(|c| c.labels.as_ref());
+    if skip_tasks && container::is_task(labels) {
+        return None;
+    }
+
+    let networks = response.typ.as_deref() == Some("network");
+
+    Some(container::get_attachment_events(
+        &container, networks, !networks,
+    ))
+}
+
+pub fn is_attachment(response: &SystemEventsResponse) -> bool {
+    matches!(
+        (response.typ.as_deref(), response.action.as_deref()),
+        (Some("network"), Some("connect"))
+            | (Some("network"), Some("disconnect"))
+            | (Some("volume"), Some("mount"))
+            | (Some("volume"), Some("unmount"))
+    )
+}
+
+#[cfg(test)]
+mod must {
+    use bollard::models::SystemEventsResponse;
+
+    fn create_response(typ: &str, action: &str) -> SystemEventsResponse {
+        SystemEventsResponse {
+            typ: Some(typ.to_owned()),
+            action: Some(action.to_owned()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn accept_network_and_volume_attachments() {
+        // arrange
+        let responses = vec![
+            create_response("network", "connect"),
+            create_response("network", "disconnect"),
+            create_response("volume", "mount"),
+            create_response("volume", "unmount"),
+        ];
+
+        // act && assert
+        for response in responses {
+            assert!(super::is_attachment(&response));
+        }
+    }
+
+    #[test]
+    fn reject_other_network_and_volume_actions() {
+        // arrange
+        let responses = vec![
+            create_response("network", "create"),
+            create_response("volume", "destroy"),
+            create_response("container", "start"),
+        ];
+
+        // act && assert
+        for response in responses {
+            assert!(!super::is_attachment(&response));
+        }
+    }
+}
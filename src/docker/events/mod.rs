@@ -1,31 +1,63 @@
 use std::collections::HashMap;
 
 use bollard::{errors::Error, models::SystemEventsResponse, system::EventsOptions, Docker};
-use tokio::{sync::broadcast, task};
+use tokio::task;
 use tokio_stream::{Stream, StreamExt};
 use tracing::error;
 
-use crate::events::{ContainerEvent, Event, EventType};
+use crate::{
+    bus::Publisher,
+    configuration::Configuration,
+    events::{ContainerEvent, Event, EventType},
+    health,
+    shutdown::{self, Cancellation},
+};
 
+mod attachment;
 mod transition;
 
-pub async fn source(event_sender: broadcast::Sender<Event>, client: Docker) {
+pub async fn source(
+    publisher: Publisher,
+    client: Docker,
+    conf: &Configuration,
+    mut cancellation: Cancellation,
+) {
+    let options = get_options(conf);
+    let skip_tasks = conf.swarm.is_some();
+
     task::spawn(async move {
-        let stream = get_event_response_stream(client).filter_map(transition::to_events);
+        let stream = futures::StreamExt::then(
+            get_event_response_stream(client.clone(), options),
+            move |result| to_events(client.clone(), result, skip_tasks),
+        )
+        .filter_map(|events| events);
 
-        receive_loop(stream, event_sender).await
+        tokio::select! {
+            _ = receive_loop(Box::pin(stream), publisher) => {}
+            _ = shutdown::cancelled(&mut cancellation) => {}
+        }
     });
 }
 
 fn get_event_response_stream(
     client: Docker,
+    options: EventsOptions<String>,
 ) -> impl Stream<Item = Result<SystemEventsResponse, Error>> {
-    client.events(Some(get_options()))
+    client.events(Some(options))
 }
 
-fn get_options() -> EventsOptions<String> {
+fn get_options(conf: &Configuration) -> EventsOptions<String> {
+    let mut types = vec!["container".to_owned()];
+    if conf.docker.track_networks {
+        types.push("network".to_owned());
+    }
+
+    if conf.docker.track_volumes {
+        types.push("volume".to_owned());
+    }
+
     let mut query = HashMap::new();
-    query.insert("type".to_owned(), vec!["container".to_owned()]);
+    query.insert("type".to_owned(), types);
 
     EventsOptions::<String> {
         since: None,
@@ -34,16 +66,37 @@ fn get_options() -> EventsOptions<String> {
     }
 }
 
-async fn receive_loop(
-    mut stream: impl Stream<Item = Vec<Event>> + Unpin,
-    event_sender: broadcast::Sender<Event>,
-) {
+async fn to_events(
+    client: Docker,
+    result: Result<SystemEventsResponse, Error>,
+    skip_tasks: bool,
+) -> Option<Vec<Event>> {
+    let response = match result {
+        Ok(rspns) => {
+            health::docker_event_received();
+            rspns
+        }
+        Err(error) => {
+            error!("could not resolve event from stream: {}", error);
+            return None;
+        }
+    };
+
+    match response.typ.as_deref() {
+        Some("network") | Some("volume") => {
+            attachment::to_events(&client, response, skip_tasks).await
+        }
+        _ if skip_tasks && transition::is_task(&response) => None,
+        _ => transition::get_events_by_response(response),
+    }
+}
+
+async fn receive_loop(mut stream: impl Stream<Item = Vec<Event>> + Unpin, publisher: Publisher) {
+    let _alive = health::alive("docker_events");
+
     while let Some(events) = stream.next().await {
         for event in events.into_iter() {
-            match event_sender.send(event) {
-                Ok(_) => {}
-                Err(e) => error!("event could not be send to event_router: {}", e),
-            }
+            publisher.send(event);
         }
     }
 }
@@ -55,15 +108,35 @@ mod must {
         time::Duration,
     };
 
-    use tokio::{sync::broadcast, task};
+    use tokio::task;
     use tokio_stream::StreamExt;
 
-    use crate::events::{Event, EventType};
+    use crate::{
+        bus,
+        configuration::Configuration,
+        events::{Event, EventType},
+    };
+
+    fn create_configuration(track_networks: bool, track_volumes: bool) -> Configuration {
+        let mut conf: Configuration = serde_yaml::from_str(
+            "
+mqtt:
+  client_id: qwert
+  host: yuio
+  port: 1234",
+        )
+        .unwrap();
+
+        conf.docker.track_networks = track_networks;
+        conf.docker.track_volumes = track_volumes;
+
+        conf
+    }
 
     #[test]
     fn filter_events_for_type_container_only() {
         // act
-        let options = super::get_options();
+        let options = super::get_options(&create_configuration(false, false));
         let mut filters = options.filters.into_iter();
 
         // assert
@@ -75,16 +148,38 @@ mod must {
         assert_eq!(None, filters.next());
     }
 
+    #[test]
+    fn filter_events_for_tracked_networks_and_volumes() {
+        // act
+        let options = super::get_options(&create_configuration(true, true));
+        let mut filters = options.filters.into_iter();
+
+        // assert
+        assert_eq!(
+            Some((
+                "type".to_owned(),
+                vec![
+                    "container".to_owned(),
+                    "network".to_owned(),
+                    "volume".to_owned()
+                ]
+            )),
+            filters.next()
+        );
+
+        assert_eq!(None, filters.next());
+    }
+
     #[tokio::test]
     async fn stop_receive_loop_if_stream_closed() {
         // arrange
         let stream = tokio_stream::empty();
-        let (event_sender, _) = broadcast::channel(500);
+        let publisher = bus::new();
 
         // act
         let timeout = tokio::time::timeout(
             Duration::from_millis(100),
-            super::receive_loop(stream, event_sender),
+            super::receive_loop(stream, publisher),
         );
 
         // assert
@@ -107,12 +202,13 @@ mod must {
             }],
         ]);
 
-        let (event_sender, mut receiver) = broadcast::channel(500);
+        let publisher = bus::new();
+        let mut subscriber = publisher.subscribe();
 
         // act
         let timeout = tokio::time::timeout(
             Duration::from_millis(100),
-            super::receive_loop(stream, event_sender),
+            super::receive_loop(stream, publisher),
         );
 
         task::spawn(async move {
@@ -122,12 +218,12 @@ mod must {
         });
 
         // assert
-        assert_eq!("test1", receiver.recv().await.unwrap().container_name);
-        assert_eq!("test2", receiver.recv().await.unwrap().container_name);
+        assert_eq!("test1", subscriber.recv().await.unwrap().container_name);
+        assert_eq!("test2", subscriber.recv().await.unwrap().container_name);
     }
 
     #[tokio::test]
-    async fn not_stop_sending_while_getting_errors() {
+    async fn not_stop_sending_without_subscribers() {
         // arrange
         let counter = Arc::new(Mutex::new(0));
         let counter_moved = counter.clone();
@@ -149,13 +245,13 @@ mod must {
             evnts
         });
 
-        let (event_sender, receiver) = broadcast::channel(500);
-        drop(receiver); // droping receiver enforces err while sending to channel
+        let publisher = bus::new();
+        drop(publisher.subscribe()); // dropped subscribers get removed while sending
 
         // act
         let timeout = tokio::time::timeout(
             Duration::from_millis(100),
-            super::receive_loop(stream, event_sender),
+            super::receive_loop(stream, publisher),
         );
 
         if timeout.await.is_err() {
@@ -1,31 +1,20 @@
-use bollard::{
-    errors::Error,
-    models::{SystemEventsResponse, SystemEventsResponseActor},
-};
-use tracing::error;
+use bollard::models::{SystemEventsResponse, SystemEventsResponseActor};
 
-use super::{ContainerEvent, Event, EventType};
-
-pub fn to_events(result: Result<SystemEventsResponse, Error>) -> Option<Vec<Event>> {
-    let response: SystemEventsResponse;
-    match result {
-        Ok(rspns) => response = rspns,
-        Err(error) => {
-            error!("could not resolve event from stream: {}", error);
-            return None;
-        }
-    }
+use crate::docker::container;
 
-    get_events_by_response(response)
-}
+use super::{ContainerEvent, Event, EventType};
 
-fn get_events_by_response(response: SystemEventsResponse) -> Option<Vec<Event>> {
+pub fn get_events_by_response(response: SystemEventsResponse) -> Option<Vec<Event>> {
     let state_event = get_state_event(&response);
 
     let mut messages = vec![];
     match &state_event.event {
         EventType::State(ContainerEvent::Undefined) => return None,
-        EventType::State(ContainerEvent::Create) => messages.push(get_image_event(&response)),
+        EventType::State(ContainerEvent::Create) => {
+            messages.push(get_image_event(&response));
+            messages.extend(get_id_event(&response));
+        }
+        EventType::State(ContainerEvent::Die) => messages.extend(get_exit_code_event(&response)),
         _ => {}
     }
 
@@ -48,7 +37,37 @@ fn get_image_event(response: &SystemEventsResponse) -> Event {
     }
 }
 
-fn get_attribute(actor: &Option<SystemEventsResponseActor>, attribute: &str) -> String {
+fn get_id_event(response: &SystemEventsResponse) -> Option<Event> {
+    let id = response
+        .actor
+        .as_ref()
+        .and_then(|actor| actor.id.to_owned())?;
+
+    Some(Event {
+        container_name: get_attribute(&response.actor, "name"),
+        event: EventType::ContainerId(id),
+    })
+}
+
+fn get_exit_code_event(response: &SystemEventsResponse) -> Option<Event> {
+    let code = get_attribute(&response.actor, "exitCode").parse().ok()?;
+
+    Some(Event {
+        container_name: get_attribute(&response.actor, "name"),
+        event: EventType::ExitCode(code),
+    })
+}
+
+pub fn is_task(response: &SystemEventsResponse) -> bool {
+    let attributes = response
+        .actor
+        .as_ref()
+        .and_then(|actor| actor.attributes.as_ref());
+
+    container::is_task(attributes)
+}
+
+pub fn get_attribute(actor: &Option<SystemEventsResponseActor>, attribute: &str) -> String {
     let mut result = "".to_owned();
     if let Some(some_actor) = actor {
         if let Some(attributes) = &some_actor.attributes {
@@ -81,7 +100,85 @@ fn get_container_event(action: &Option<String>) -> ContainerEvent {
 
 #[cfg(test)]
 mod must {
-    use bollard::models::SystemEventsResponse;
+    use std::collections::HashMap;
+
+    use bollard::models::{SystemEventsResponse, SystemEventsResponseActor};
+
+    #[test]
+    fn detect_swarm_task_containers() {
+        // arrange
+        let mut attributes = HashMap::new();
+        attributes.insert("name".to_owned(), "web.1.x7f3k".to_owned());
+        attributes.insert("com.docker.swarm.service.name".to_owned(), "web".to_owned());
+
+        let response = SystemEventsResponse {
+            actor: Some(SystemEventsResponseActor {
+                id: None,
+                attributes: Some(attributes),
+            }),
+            ..Default::default()
+        };
+
+        // act
+        let task = super::is_task(&response);
+
+        // assert
+        assert!(task);
+        assert!(!super::is_task(&create_response("start")));
+    }
+
+    #[test]
+    fn return_id_on_create() {
+        // arrange
+        let mut attributes = HashMap::new();
+        attributes.insert("name".to_owned(), "borg".to_owned());
+
+        let response = SystemEventsResponse {
+            action: Some("create".to_owned()),
+            actor: Some(SystemEventsResponseActor {
+                id: Some("4a1b".to_owned()),
+                attributes: Some(attributes),
+            }),
+            ..Default::default()
+        };
+
+        // act
+        let events = super::get_events_by_response(response).unwrap();
+
+        // assert
+        assert_eq!(3, events.len());
+        assert_eq!(
+            super::EventType::ContainerId("4a1b".to_owned()),
+            events[1].event
+        );
+    }
+
+    #[test]
+    fn return_exit_code_on_die() {
+        // arrange
+        let mut attributes = HashMap::new();
+        attributes.insert("name".to_owned(), "borg".to_owned());
+        attributes.insert("exitCode".to_owned(), "137".to_owned());
+
+        let response = SystemEventsResponse {
+            action: Some("die".to_owned()),
+            actor: Some(SystemEventsResponseActor {
+                id: None,
+                attributes: Some(attributes),
+            }),
+            ..Default::default()
+        };
+
+        // act
+        let events = super::get_events_by_response(response).unwrap();
+
+        // assert
+        assert_eq!(super::EventType::ExitCode(137), events[0].event);
+        assert_eq!(
+            super::EventType::State(super::ContainerEvent::Die),
+            events[1].event
+        );
+    }
 
     #[test]
     fn return_none_if_action_is_undefined() {
@@ -135,10 +232,7 @@ mod must {
         for (response, result_count) in responses {
             assert_eq!(
                 result_count,
-                super::get_events_by_response(response)
-                    .unwrap()
-                    .iter()
-                    .count()
+                super::get_events_by_response(response).unwrap().len()
             );
         }
     }
@@ -0,0 +1,84 @@
+use bollard::models::SystemInfo;
+
+use crate::events::{Event, EventType, HOST};
+
+pub fn get_events(info: &SystemInfo) -> Vec<Event> {
+    vec![
+        create_event(EventType::EngineVersion(get_text(&info.server_version))),
+        create_event(EventType::OperatingSystem(get_text(&info.operating_system))),
+        create_event(EventType::KernelVersion(get_text(&info.kernel_version))),
+        create_event(EventType::Cpus(get_number(info.ncpu))),
+        create_event(EventType::TotalMemory(get_number(info.mem_total))),
+    ]
+}
+
+pub fn get_reachable_event(reachable: bool) -> Event {
+    create_event(EventType::Reachable(reachable))
+}
+
+fn get_text(value: &Option<String>) -> String {
+    value.to_owned().unwrap_or_default()
+}
+
+fn get_number(value: Option<i64>) -> u64 {
+    match value {
+        Some(value) if value > 0 => value as u64,
+        _ => 0,
+    }
+}
+
+fn create_event(event: EventType) -> Event {
+    Event {
+        container_name: HOST.to_owned(),
+        event,
+    }
+}
+
+#[cfg(test)]
+mod must {
+    use bollard::models::SystemInfo;
+
+    use crate::events::{EventType, HOST};
+
+    #[test]
+    fn return_engine_information_for_host() {
+        // arrange
+        let info = SystemInfo {
+            server_version: Some("20.10.8".to_owned()),
+            operating_system: Some("Debian GNU/Linux 11 (bullseye)".to_owned()),
+            kernel_version: Some("5.10.0-8-amd64".to_owned()),
+            ncpu: Some(4),
+            mem_total: Some(8_000_000_000),
+            ..Default::default()
+        };
+
+        // act
+        let events = super::get_events(&info);
+
+        // assert
+        assert!(events.iter().all(|event| event.container_name == HOST));
+        assert_eq!(
+            vec![
+                EventType::EngineVersion("20.10.8".to_owned()),
+                EventType::OperatingSystem("Debian GNU/Linux 11 (bullseye)".to_owned()),
+                EventType::KernelVersion("5.10.0-8-amd64".to_owned()),
+                EventType::Cpus(4),
+                EventType::TotalMemory(8_000_000_000),
+            ],
+            events
+                .into_iter()
+                .map(|event| event.event)
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn return_defaults_for_missing_information() {
+        // act
+        let events = super::get_events(&SystemInfo::default());
+
+        // assert
+        assert_eq!(EventType::EngineVersion("".to_owned()), events[0].event);
+        assert_eq!(EventType::Cpus(0), events[3].event);
+    }
+}
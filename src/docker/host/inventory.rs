@@ -0,0 +1,218 @@
+use bollard::models::{ImageSummary, Network, SystemDataUsageResponse, Volume};
+
+use crate::events::{Event, EventType, HOST};
+
+pub fn get_events(usage: &SystemDataUsageResponse) -> Vec<Event> {
+    let images = usage.images.as_deref().unwrap_or_default();
+    let volumes = usage.volumes.as_deref().unwrap_or_default();
+    let build_cache = usage.build_cache.as_deref().unwrap_or_default();
+
+    let images_size = match usage.layers_size {
+        Some(size) => size,
+        None => images.iter().map(|image| image.size).sum(),
+    };
+
+    let volumes_size = volumes
+        .iter()
+        .filter_map(|volume| volume.usage_data.as_ref())
+        .map(|usage_data| usage_data.size)
+        .filter(|size| *size > 0)
+        .sum();
+
+    let build_cache_size = build_cache
+        .iter()
+        .filter_map(|cache| cache.size)
+        .filter(|size| *size > 0)
+        .sum();
+
+    vec![
+        create_event(EventType::Images(images.len() as u64)),
+        create_event(EventType::DanglingImages(
+            images.iter().filter(|image| is_dangling(image)).count() as u64,
+        )),
+        create_event(EventType::ImagesSize(to_size(images_size))),
+        create_event(EventType::VolumesSize(to_size(volumes_size))),
+        create_event(EventType::BuildCacheSize(to_size(build_cache_size))),
+    ]
+}
+
+pub fn get_unused_networks_event(networks: &[Network]) -> Event {
+    let mut names: Vec<String> = networks
+        .iter()
+        .filter(|network| !is_predefined(network))
+        .filter(|network| {
+            network
+                .containers
+                .as_ref()
+                .map(|containers| containers.is_empty())
+                .unwrap_or(true)
+        })
+        .filter_map(|network| network.name.to_owned())
+        .collect();
+    names.sort();
+
+    create_event(EventType::UnusedNetworks(names))
+}
+
+pub fn get_unused_volumes_event(volumes: &[Volume]) -> Event {
+    let mut names: Vec<String> = volumes
+        .iter()
+        .map(|volume| volume.name.to_owned())
+        .collect();
+    names.sort();
+
+    create_event(EventType::UnusedVolumes(names))
+}
+
+fn is_predefined(network: &Network) -> bool {
+    matches!(
+        network.name.as_deref(),
+        Some("bridge") | Some("host") | Some("none") | Some("docker_gwbridge") | Some("ingress")
+    )
+}
+
+fn is_dangling(image: &ImageSummary) -> bool {
+    image
+        .repo_tags
+        .iter()
+        .all(|tag| tag.as_str() == "<none>:<none>")
+}
+
+fn to_size(size: i64) -> u64 {
+    if size < 0 {
+        0
+    } else {
+        size as u64
+    }
+}
+
+fn create_event(event: EventType) -> Event {
+    Event {
+        container_name: HOST.to_owned(),
+        event,
+    }
+}
+
+#[cfg(test)]
+mod must {
+    use std::collections::HashMap;
+
+    use bollard::models::{
+        BuildCache, ImageSummary, Network, NetworkContainer, SystemDataUsageResponse, Volume,
+        VolumeUsageData,
+    };
+
+    use crate::events::EventType;
+
+    fn create_image(repo_tags: Vec<&str>, size: i64) -> ImageSummary {
+        ImageSummary {
+            repo_tags: repo_tags.into_iter().map(|t| t.to_owned()).collect(),
+            size,
+            ..Default::default()
+        }
+    }
+
+    fn create_volume(size: i64) -> Volume {
+        Volume {
+            usage_data: Some(VolumeUsageData { size, ref_count: 0 }),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn count_images_and_dangling_images() {
+        // arrange
+        let usage = SystemDataUsageResponse {
+            images: Some(vec![
+                create_image(vec!["nginx:latest"], 10),
+                create_image(vec![], 20),
+                create_image(vec!["<none>:<none>"], 30),
+            ]),
+            ..Default::default()
+        };
+
+        // act
+        let events = super::get_events(&usage);
+
+        // assert
+        assert_eq!(EventType::Images(3), events[0].event);
+        assert_eq!(EventType::DanglingImages(2), events[1].event);
+        assert_eq!(EventType::ImagesSize(60), events[2].event);
+    }
+
+    #[test]
+    fn prefer_layers_size_for_images_size() {
+        // arrange
+        let usage = SystemDataUsageResponse {
+            layers_size: Some(42),
+            images: Some(vec![create_image(vec!["nginx:latest"], 10)]),
+            ..Default::default()
+        };
+
+        // act
+        let events = super::get_events(&usage);
+
+        // assert
+        assert_eq!(EventType::ImagesSize(42), events[2].event);
+    }
+
+    #[test]
+    fn ignore_unknown_sizes_of_volumes_and_build_cache() {
+        // arrange
+        let usage = SystemDataUsageResponse {
+            volumes: Some(vec![create_volume(-1), create_volume(5)]),
+            build_cache: Some(vec![
+                BuildCache {
+                    size: Some(7),
+                    ..Default::default()
+                },
+                BuildCache {
+                    size: None,
+                    ..Default::default()
+                },
+            ]),
+            ..Default::default()
+        };
+
+        // act
+        let events = super::get_events(&usage);
+
+        // assert
+        assert_eq!(EventType::VolumesSize(5), events[3].event);
+        assert_eq!(EventType::BuildCacheSize(7), events[4].event);
+    }
+
+    #[test]
+    fn report_user_defined_networks_without_containers() {
+        // arrange
+        let mut containers = HashMap::new();
+        containers.insert("id".to_owned(), NetworkContainer::default());
+
+        let networks = vec![
+            Network {
+                name: Some("bridge".to_owned()),
+                containers: Some(HashMap::new()),
+                ..Default::default()
+            },
+            Network {
+                name: Some("web_default".to_owned()),
+                containers: Some(containers),
+                ..Default::default()
+            },
+            Network {
+                name: Some("old_default".to_owned()),
+                containers: Some(HashMap::new()),
+                ..Default::default()
+            },
+        ];
+
+        // act
+        let event = super::get_unused_networks_event(&networks);
+
+        // assert
+        assert_eq!(
+            EventType::UnusedNetworks(vec!["old_default".to_owned()]),
+            event.event
+        );
+    }
+}
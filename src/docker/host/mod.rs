@@ -0,0 +1,193 @@
+use std::{collections::HashMap, time::Duration};
+
+use bollard::{
+    models::{Network, Volume},
+    network::{InspectNetworkOptions, ListNetworksOptions},
+    system::EventsOptions,
+    volume::ListVolumesOptions,
+    Docker,
+};
+use tokio::{
+    task,
+    time::{self, Instant},
+};
+use tokio_stream::StreamExt;
+use tracing::error;
+
+use crate::{
+    bus::{Publisher, Subscriber},
+    configuration::{self, Configuration},
+    events::{ContainerEvent, Event, EventType, HOST},
+    shutdown::{self, Cancellation},
+};
+
+use self::totals::Totals;
+
+mod info;
+mod inventory;
+mod totals;
+
+const INVENTORY_EVENT_DELAY: Duration = Duration::from_secs(5);
+
+pub async fn source(
+    mut subscriber: Subscriber,
+    publisher: Publisher,
+    client: Docker,
+    conf: &Configuration,
+    mut cancellation: Cancellation,
+) {
+    let host = match &conf.host {
+        Some(host) => host.clone(),
+        None => return,
+    };
+
+    let docker = conf.docker.clone();
+
+    task::spawn(async move {
+        publisher.send(Event {
+            container_name: HOST.to_owned(),
+            event: EventType::State(ContainerEvent::Create),
+        });
+
+        let mut inventory_events = client.events(Some(get_options(&docker)));
+        let mut interval = time::interval(Duration::from_secs(host.inventory_interval));
+        let mut ping_interval = time::interval(Duration::from_secs(host.ping_interval));
+        let mut deadline: Option<Instant> = None;
+
+        let mut reachable = None;
+        let mut totals = Totals::default();
+
+        loop {
+            tokio::select! {
+                received = subscriber.recv() => match received {
+                    Some(event) => {
+                        if totals.update(&event) {
+                            for event in totals.get_events() {
+                                publisher.send(event);
+                            }
+                        }
+                    }
+                    None => break,
+                },
+                _ = ping_interval.tick() => {
+                    let current = client.ping().await.is_ok();
+                    if reachable != Some(current) {
+                        reachable = Some(current);
+                        publisher.send(info::get_reachable_event(current));
+
+                        if current {
+                            send_info(&client, &publisher).await;
+                        }
+                    }
+                }
+                _ = interval.tick() => send_inventory(&client, &docker, &publisher).await,
+                Some(_) = inventory_events.next() => {
+                    deadline.get_or_insert_with(|| Instant::now() + INVENTORY_EVENT_DELAY);
+                }
+                _ = time::sleep_until(deadline.unwrap_or_else(Instant::now)), if deadline.is_some() => {
+                    deadline = None;
+                    send_inventory(&client, &docker, &publisher).await;
+                }
+                _ = shutdown::cancelled(&mut cancellation) => break,
+            }
+        }
+    });
+}
+
+fn get_options(docker: &configuration::Docker) -> EventsOptions<String> {
+    let mut types = vec!["image".to_owned()];
+    if docker.track_networks {
+        types.push("network".to_owned());
+    }
+
+    if docker.track_volumes {
+        types.push("volume".to_owned());
+    }
+
+    let mut query = HashMap::new();
+    query.insert("type".to_owned(), types);
+
+    EventsOptions::<String> {
+        since: None,
+        until: None,
+        filters: query,
+    }
+}
+
+async fn send_inventory(client: &Docker, docker: &configuration::Docker, publisher: &Publisher) {
+    match client.df().await {
+        Ok(usage) => {
+            for event in inventory::get_events(&usage) {
+                publisher.send(event);
+            }
+        }
+        Err(e) => error!("could not resolve disk usage: {}", e),
+    }
+
+    if docker.track_networks {
+        if let Some(networks) = get_networks(client).await {
+            publisher.send(inventory::get_unused_networks_event(&networks));
+        }
+    }
+
+    if docker.track_volumes {
+        if let Some(volumes) = get_unused_volumes(client).await {
+            publisher.send(inventory::get_unused_volumes_event(&volumes));
+        }
+    }
+}
+
+async fn send_info(client: &Docker, publisher: &Publisher) {
+    match client.info().await {
+        Ok(system_info) => {
+            for event in info::get_events(&system_info) {
+                publisher.send(event);
+            }
+        }
+        Err(e) => error!("could not resolve docker info: {}", e),
+    }
+}
+
+async fn get_networks(client: &Docker) -> Option<Vec<Network>> {
+    let networks = match client
+        .list_networks(None::<ListNetworksOptions<String>>)
+        .await
+    {
+        Ok(networks) => networks,
+        Err(e) => {
+            error!("could not resolve networks: {}", e);
+            return None;
+        }
+    };
+
+    // listing networks does not resolve attached containers
+    let mut result = vec![];
+    for network in networks {
+        let id = network.id.as_deref().unwrap_or_default();
+        match client
+            .inspect_network(id, None::<InspectNetworkOptions<String>>)
+            .await
+        {
+            Ok(network) => result.push(network),
+            Err(e) => error!("could not inspect network {}: {}", id, e),
+        }
+    }
+
+    Some(result)
+}
+
+async fn get_unused_volumes(client: &Docker) -> Option<Vec<Volume>> {
+    let mut filters = HashMap::new();
+    filters.insert("dangling".to_owned(), vec!["true".to_owned()]);
+
+    match client
+        .list_volumes(Some(ListVolumesOptions::<String> { filters }))
+        .await
+    {
+        Ok(response) => Some(response.volumes),
+        Err(e) => {
+            error!("could not resolve volumes: {}", e);
+            None
+        }
+    }
+}
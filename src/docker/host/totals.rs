@@ -0,0 +1,143 @@
+use std::collections::HashMap;
+
+use crate::events::{self, ContainerEvent, Event, EventType, HOST};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Status {
+    Paused,
+    Running,
+    Stopped,
+}
+
+#[derive(Default)]
+pub struct Totals {
+    containers: HashMap<String, Status>,
+}
+
+impl Totals {
+    pub fn update(&mut self, event: &Event) -> bool {
+        if !events::is_container(&event.container_name) {
+            return false;
+        }
+
+        let container_event = match &event.event {
+            EventType::State(container_event) => container_event,
+            _ => return false,
+        };
+
+        let status = match container_event {
+            ContainerEvent::Create | ContainerEvent::Die | ContainerEvent::Stop => Status::Stopped,
+            ContainerEvent::Restart | ContainerEvent::Start | ContainerEvent::Unpause => {
+                Status::Running
+            }
+            ContainerEvent::Pause => Status::Paused,
+            ContainerEvent::Destroy | ContainerEvent::Prune => {
+                return self.containers.remove(&event.container_name).is_some();
+            }
+            ContainerEvent::Kill | ContainerEvent::Rename | ContainerEvent::Undefined => {
+                return false;
+            }
+        };
+
+        self.containers
+            .insert(event.container_name.to_owned(), status)
+            != Some(status)
+    }
+
+    pub fn get_events(&self) -> Vec<Event> {
+        vec![
+            create_event(EventType::ContainersRunning(self.count(Status::Running))),
+            create_event(EventType::ContainersPaused(self.count(Status::Paused))),
+            create_event(EventType::ContainersStopped(self.count(Status::Stopped))),
+        ]
+    }
+
+    fn count(&self, status: Status) -> u64 {
+        self.containers.values().filter(|s| **s == status).count() as u64
+    }
+}
+
+fn create_event(event: EventType) -> Event {
+    Event {
+        container_name: HOST.to_owned(),
+        event,
+    }
+}
+
+#[cfg(test)]
+mod must {
+    use crate::events::{ContainerEvent, Event, EventType};
+
+    use super::Totals;
+
+    fn create_event(container_name: &str, container_event: ContainerEvent) -> Event {
+        Event {
+            container_name: container_name.to_owned(),
+            event: EventType::State(container_event),
+        }
+    }
+
+    #[test]
+    fn count_containers_by_last_state() {
+        // arrange
+        let mut totals = Totals::default();
+
+        // act
+        totals.update(&create_event("first", ContainerEvent::Create));
+        totals.update(&create_event("first", ContainerEvent::Start));
+        totals.update(&create_event("second", ContainerEvent::Start));
+        totals.update(&create_event("second", ContainerEvent::Pause));
+        totals.update(&create_event("third", ContainerEvent::Create));
+
+        // assert
+        assert_eq!(
+            vec![
+                EventType::ContainersRunning(1),
+                EventType::ContainersPaused(1),
+                EventType::ContainersStopped(1),
+            ],
+            totals
+                .get_events()
+                .into_iter()
+                .map(|event| event.event)
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn forget_destroyed_containers() {
+        // arrange
+        let mut totals = Totals::default();
+        totals.update(&create_event("first", ContainerEvent::Stop));
+
+        // act
+        let changed = totals.update(&create_event("first", ContainerEvent::Destroy));
+
+        // assert
+        assert!(changed);
+        assert_eq!(
+            EventType::ContainersStopped(0),
+            totals.get_events()[2].event
+        );
+    }
+
+    #[test]
+    fn report_unchanged_totals() {
+        // arrange
+        let mut totals = Totals::default();
+        totals.update(&create_event("first", ContainerEvent::Start));
+
+        // act
+        let restarted = totals.update(&create_event("first", ContainerEvent::Restart));
+        let killed = totals.update(&create_event("first", ContainerEvent::Kill));
+        let usage = totals.update(&Event {
+            container_name: "first".to_owned(),
+            event: EventType::CpuUsage(1.0),
+        });
+
+        // assert
+        assert!(!restarted);
+        assert!(!killed);
+        assert!(!usage);
+    }
+}
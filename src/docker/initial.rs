@@ -1,31 +1,54 @@
 use bollard::{models::ContainerSummaryInner, Docker};
 use std::collections::HashSet;
-use tokio::{
-    sync::{broadcast, oneshot},
-    task,
-};
-use tracing::error;
+use tokio::{sync::oneshot, task};
 
-use crate::events::{ContainerEvent, Event, EventType};
+use crate::{
+    bus::Publisher,
+    configuration::Configuration,
+    events::{ContainerEvent, Event, EventType},
+    persistence::Snapshot,
+};
 
 use super::container;
 
 pub async fn source(
-    event_sender: broadcast::Sender<Event>,
-    repo_init_receiver: oneshot::Receiver<Vec<String>>,
+    publisher: Publisher,
+    repo_init_receiver: oneshot::Receiver<Vec<Snapshot>>,
     client: Docker,
+    conf: &Configuration,
 ) {
+    let conf = conf.clone();
     task::spawn(async move {
-        let containers = container::get(&client).await;
+        let containers: Vec<ContainerSummaryInner> = container::get(&client)
+            .await
+            .into_iter()
+            .filter(|c| conf.swarm.is_none() || !container::is_task(c.labels.as_ref()))
+            .collect();
 
-        handle_orphaned_containers(&event_sender, repo_init_receiver, &containers).await;
+        handle_orphaned_containers(&publisher, repo_init_receiver, &containers).await;
+
+        let ids: Vec<String> = containers.iter().filter_map(|c| c.id.to_owned()).collect();
 
         containers
             .into_iter()
             .flat_map(get_events_by_container)
             .for_each(|event| {
-                send_event(event, &event_sender);
+                send_event(event, &publisher);
             });
+
+        if conf.docker.track_networks || conf.docker.track_volumes {
+            for id in ids {
+                if let Some(container) = container::inspect(&client, &id).await {
+                    container::get_attachment_events(
+                        &container,
+                        conf.docker.track_networks,
+                        conf.docker.track_volumes,
+                    )
+                    .into_iter()
+                    .for_each(|event| send_event(event, &publisher));
+                }
+            }
+        }
     });
 }
 
@@ -43,6 +66,13 @@ fn get_events_by_container(container: ContainerSummaryInner) -> Vec<Event> {
         },
     ];
 
+    if let Some(id) = &container.id {
+        events.push(Event {
+            container_name: container_name.to_owned(),
+            event: EventType::ContainerId(id.to_owned()),
+        });
+    }
+
     if let Some(image) = &container.image {
         events.push(Event {
             container_name,
@@ -53,16 +83,12 @@ fn get_events_by_container(container: ContainerSummaryInner) -> Vec<Event> {
     events
 }
 
-fn send_event(event: Event, event_sender: &broadcast::Sender<Event>) {
+fn send_event(event: Event, publisher: &Publisher) {
     if let EventType::State(ContainerEvent::Undefined) = &event.event {
         return;
     }
 
-    // TODO refactor send message function with retry and warning if ok > 1
-    match event_sender.send(event) {
-        Ok(_) => {}
-        Err(e) => error!("event could not be send to event_router: {}", e),
-    }
+    publisher.send_initial(event);
 }
 
 fn get_state(container: &ContainerSummaryInner) -> ContainerEvent {
@@ -80,35 +106,49 @@ fn get_state(container: &ContainerSummaryInner) -> ContainerEvent {
 }
 
 async fn handle_orphaned_containers(
-    event_sender: &broadcast::Sender<Event>,
-    repo_init_receiver: oneshot::Receiver<Vec<String>>,
+    publisher: &Publisher,
+    repo_init_receiver: oneshot::Receiver<Vec<Snapshot>>,
     containers: &[ContainerSummaryInner],
 ) {
     let docker_container_names: HashSet<String> = containers
         .iter()
-        .map(|c| container::get_name(&c).to_owned())
+        .map(|c| container::get_name(c).to_owned())
         .collect();
 
+    // retained topics of the snapshot get cleared before the destroy clears what the name implies
     repo_init_receiver
         .await
         .unwrap_or_default()
         .into_iter()
-        .filter(|c| !docker_container_names.contains(c))
-        .map(|c| Event {
-            container_name: c,
-            event: EventType::State(ContainerEvent::Destroy),
+        .filter(|snapshot| !docker_container_names.contains(&snapshot.container_name))
+        .flat_map(|snapshot| {
+            vec![
+                Event {
+                    container_name: snapshot.container_name.to_owned(),
+                    event: EventType::Orphaned(snapshot.topics),
+                },
+                Event {
+                    container_name: snapshot.container_name,
+                    event: EventType::State(ContainerEvent::Destroy),
+                },
+            ]
         })
-        .for_each(|e| send_event(e, &event_sender));
+        // removals while docker2mqtt was down are real changes, thus not sent as initial events
+        .for_each(|e| publisher.send(e));
 }
 
 #[cfg(test)]
 mod must {
     use bollard::models::ContainerSummaryInner;
-    use tokio::sync::{broadcast, oneshot};
+    use tokio::sync::oneshot;
 
-    use crate::events::{ContainerEvent, Event, EventType};
+    use crate::{
+        bus,
+        events::{ContainerEvent, Event, EventType},
+        persistence::Snapshot,
+    };
 
-    use super::handle_orphaned_containers;
+    use super::{handle_orphaned_containers, send_event};
 
     fn create_container_summary(name: String) -> ContainerSummaryInner {
         ContainerSummaryInner {
@@ -133,23 +173,72 @@ mod must {
     #[tokio::test]
     async fn return_correct_remove_events_for_orphaned_containers() {
         let (repo_init_sender, repo_init_receiver) = oneshot::channel();
-        let (mqtt_sender, mut mqtt_receiver) = broadcast::channel(100);
+        let publisher = bus::new();
+        let mut subscriber = publisher.subscribe();
 
         let container_names: Vec<ContainerSummaryInner> = vec!["first", "second"]
             .into_iter()
             .map(|c| create_container_summary(c.to_owned()))
             .collect();
 
-        if let Err(e) = repo_init_sender.send(vec![String::from("second"), String::from("third")]) {
+        let snapshots = vec![
+            create_snapshot("second", vec![]),
+            create_snapshot("third", vec!["homeassistant/sensor/third/config"]),
+        ];
+
+        if let Err(e) = repo_init_sender.send(snapshots) {
             panic!("error in test: {:?}", e)
         }
 
-        handle_orphaned_containers(&mqtt_sender, repo_init_receiver, &container_names).await;
+        handle_orphaned_containers(&publisher, repo_init_receiver, &container_names).await;
+
+        let expected = Event {
+            container_name: "third".to_owned(),
+            event: EventType::Orphaned(vec!["homeassistant/sensor/third/config".to_owned()]),
+        };
+        assert_eq!(expected, subscriber.recv().await.unwrap());
 
         let expected = Event {
             container_name: "third".to_owned(),
             event: EventType::State(ContainerEvent::Destroy),
         };
-        assert_eq!(expected, mqtt_receiver.recv().await.unwrap());
+        assert_eq!(expected, subscriber.recv().await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn keep_current_states_from_change_subscribers() {
+        // arrange
+        let (repo_init_sender, repo_init_receiver) = oneshot::channel();
+        let publisher = bus::new();
+        let mut subscriber = publisher.subscribe_changes();
+        repo_init_sender
+            .send(vec![create_snapshot("third", vec![])])
+            .unwrap();
+
+        // act
+        send_event(
+            Event {
+                container_name: "first".to_owned(),
+                event: EventType::State(ContainerEvent::Create),
+            },
+            &publisher,
+        );
+        handle_orphaned_containers(&publisher, repo_init_receiver, &[]).await;
+
+        // assert
+        let expected = Event {
+            container_name: "third".to_owned(),
+            event: EventType::State(ContainerEvent::Destroy),
+        };
+        assert_eq!("third", subscriber.recv().await.unwrap().container_name);
+        assert_eq!(expected, subscriber.recv().await.unwrap());
+    }
+
+    fn create_snapshot(container_name: &str, topics: Vec<&str>) -> Snapshot {
+        Snapshot {
+            container_name: container_name.to_owned(),
+            topics: topics.into_iter().map(String::from).collect(),
+            ..Default::default()
+        }
     }
 }
@@ -0,0 +1,172 @@
+use std::{collections::HashMap, time::Duration};
+
+use regex::Regex;
+use tokio::time::Instant;
+use tracing::warn;
+
+use crate::{
+    configuration::LogAlert,
+    events::{Alert, LogLine},
+};
+
+// the same rule fires at most once within the cooldown
+const ALERT_COOLDOWN: Duration = Duration::from_secs(10);
+
+struct Rule {
+    name: String,
+    pattern: Regex,
+    severity: String,
+}
+
+pub struct Alerts {
+    rules: Vec<Rule>,
+    timeout: Duration,
+    fired: HashMap<String, Instant>,
+    expires: Option<Instant>,
+}
+
+impl Alerts {
+    pub fn new(alerts: &[LogAlert], timeout: u64) -> Alerts {
+        let mut rules = vec![];
+        for alert in alerts {
+            match Regex::new(&alert.pattern) {
+                Ok(pattern) => rules.push(Rule {
+                    name: alert.name.to_owned(),
+                    pattern,
+                    severity: alert.severity.to_owned(),
+                }),
+                Err(e) => warn!("creating log alert {} (regex) failed: {}", alert.name, e),
+            }
+        }
+
+        Alerts {
+            rules,
+            timeout: Duration::from_secs(timeout),
+            fired: HashMap::new(),
+            expires: None,
+        }
+    }
+
+    pub fn check(&mut self, line: &LogLine, now: Instant) -> Option<Alert> {
+        let rule = self
+            .rules
+            .iter()
+            .find(|rule| rule.pattern.is_match(&line.message))?;
+
+        if let Some(fired) = self.fired.get(&rule.name) {
+            if now.duration_since(*fired) < ALERT_COOLDOWN {
+                return None;
+            }
+        }
+
+        self.fired.insert(rule.name.to_owned(), now);
+        self.expires = Some(now + self.timeout);
+
+        Some(Alert {
+            name: rule.name.to_owned(),
+            severity: rule.severity.to_owned(),
+            message: line.message.to_owned(),
+            timestamp: line.timestamp.to_owned(),
+        })
+    }
+
+    pub fn expires(&self) -> Option<Instant> {
+        self.expires
+    }
+
+    pub fn clear(&mut self) {
+        self.expires = None;
+    }
+}
+
+#[cfg(test)]
+mod must {
+    use std::time::Duration;
+
+    use tokio::time::Instant;
+
+    use crate::{
+        configuration::LogAlert,
+        events::{Alert, LogLine},
+    };
+
+    use super::Alerts;
+
+    fn create_alerts() -> Alerts {
+        Alerts::new(
+            &[
+                LogAlert {
+                    name: "db_refused".to_owned(),
+                    pattern: "database connection refused".to_owned(),
+                    severity: "critical".to_owned(),
+                },
+                LogAlert {
+                    name: "invalid".to_owned(),
+                    pattern: "(".to_owned(),
+                    severity: "warning".to_owned(),
+                },
+            ],
+            60,
+        )
+    }
+
+    fn create_line(message: &str) -> LogLine {
+        LogLine {
+            message: message.to_owned(),
+            timestamp: Some("2021-08-10T19:10:01Z".to_owned()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn fire_alert_for_matching_line() {
+        // arrange
+        let mut alerts = create_alerts();
+        let now = Instant::now();
+
+        // act
+        let alert = alerts.check(&create_line("error: database connection refused"), now);
+
+        // assert
+        assert_eq!(
+            Some(Alert {
+                name: "db_refused".to_owned(),
+                severity: "critical".to_owned(),
+                message: "error: database connection refused".to_owned(),
+                timestamp: Some("2021-08-10T19:10:01Z".to_owned()),
+            }),
+            alert
+        );
+        assert_eq!(Some(now + Duration::from_secs(60)), alerts.expires());
+    }
+
+    #[test]
+    fn ignore_lines_without_match() {
+        // arrange
+        let mut alerts = create_alerts();
+
+        // act
+        let alert = alerts.check(&create_line("all good"), Instant::now());
+
+        // assert
+        assert_eq!(None, alert);
+        assert_eq!(None, alerts.expires());
+    }
+
+    #[test]
+    fn suppress_repeated_alerts_within_cooldown() {
+        // arrange
+        let mut alerts = create_alerts();
+        let now = Instant::now();
+        let line = create_line("database connection refused");
+        alerts.check(&line, now);
+
+        // act
+        let repeated = alerts.check(&line, now + Duration::from_secs(5));
+        let later = alerts.check(&line, now + Duration::from_secs(10));
+
+        // assert
+        assert_eq!(None, repeated);
+        assert!(later.is_some());
+    }
+}
@@ -1,11 +1,13 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 use bollard::Docker;
-use tokio::{sync::broadcast, task::JoinHandle};
+use tokio::task::JoinHandle;
 
 use crate::{
+    bus::Publisher,
     configuration::Configuration,
     events::{ContainerEvent, Event, EventType},
+    metrics,
 };
 
 use super::{stream, validate};
@@ -13,31 +15,144 @@ use super::{stream, validate};
 pub async fn event(
     event: Event,
     tasks: &mut HashMap<String, JoinHandle<()>>,
+    running: &mut HashSet<String>,
     client: &Docker,
-    event_sender: &broadcast::Sender<Event>,
+    publisher: &Publisher,
     conf: &Configuration,
 ) {
     match &event.event {
         EventType::State(ContainerEvent::Start) => {
-            if !validate::target(&event, client, conf).await {
-                return;
-            }
-
-            tasks.insert(
-                event.container_name.to_owned(),
-                stream::start(client.clone(), event.clone(), event_sender.clone()).await,
-            );
+            running.insert(event.container_name.to_owned());
+            start(&event, tasks, client, publisher, conf).await;
         }
         EventType::State(ContainerEvent::Stop) => {
-            if let Some(handle) = tasks.remove(&event.container_name) {
-                handle.abort();
-            }
+            running.remove(&event.container_name);
+            stop(&event.container_name, tasks);
         }
         EventType::State(ContainerEvent::Die) => {
-            if let Some(handle) = tasks.remove(&event.container_name) {
-                handle.abort();
-            }
+            running.remove(&event.container_name);
+            stop(&event.container_name, tasks);
         }
         _ => {}
     }
+
+    metrics::set_log_streams(tasks.len());
+}
+
+// streams get restarted, because filters, parsers and limits are bound on start
+pub async fn reload(
+    tasks: &mut HashMap<String, JoinHandle<()>>,
+    running: &HashSet<String>,
+    client: &Docker,
+    publisher: &Publisher,
+    previous: &Configuration,
+    conf: &Configuration,
+) {
+    for container_name in get_changed(running, previous, conf) {
+        let event = Event {
+            container_name,
+            event: EventType::State(ContainerEvent::Start),
+        };
+
+        stop(&event.container_name, tasks);
+        start(&event, tasks, client, publisher, conf).await;
+    }
+
+    metrics::set_log_streams(tasks.len());
+}
+
+fn get_changed(
+    running: &HashSet<String>,
+    previous: &Configuration,
+    conf: &Configuration,
+) -> Vec<String> {
+    running
+        .iter()
+        .filter(|name| previous.docker.get_log_settings(name) != conf.docker.get_log_settings(name))
+        .cloned()
+        .collect()
+}
+
+async fn start(
+    event: &Event,
+    tasks: &mut HashMap<String, JoinHandle<()>>,
+    client: &Docker,
+    publisher: &Publisher,
+    conf: &Configuration,
+) {
+    if !conf.docker.stream_logs || !validate::target(event, client, conf).await {
+        return;
+    }
+
+    tasks.insert(
+        event.container_name.to_owned(),
+        stream::start(client.clone(), event.clone(), publisher.clone(), conf).await,
+    );
+}
+
+fn stop(container_name: &str, tasks: &mut HashMap<String, JoinHandle<()>>) {
+    if let Some(handle) = tasks.remove(container_name) {
+        handle.abort();
+    }
+}
+
+#[cfg(test)]
+mod must {
+    use std::collections::HashSet;
+
+    use super::get_changed;
+
+    fn parse(buffer: &str) -> super::Configuration {
+        serde_yaml::from_str(buffer).unwrap()
+    }
+
+    #[test]
+    fn restart_streams_with_changed_settings_only() {
+        // arrange
+        let previous = parse(
+            "
+docker:
+  stats_interval: 10
+  stream_logs_container:
+    - borg
+    - nginx
+  stream_logs_parser:
+    borg:
+      format: json
+    nginx:
+      format: json
+
+mqtt:
+  client_id: qwert
+  host: yuio
+  port: 1234",
+        );
+
+        let conf = parse(
+            "
+docker:
+  stats_interval: 60
+  stream_logs_container:
+    - borg
+    - nginx
+  stream_logs_parser:
+    nginx:
+      format: json
+    borg:
+      format: logfmt
+
+mqtt:
+  client_id: qwert
+  host: yuio
+  port: 1234",
+        );
+
+        let running: HashSet<String> = ["borg", "nginx"].iter().map(|s| s.to_string()).collect();
+
+        // act
+        let changed = get_changed(&running, &previous, &conf);
+
+        // assert
+        assert_eq!(vec!["borg"], changed);
+    }
 }
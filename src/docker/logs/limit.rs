@@ -0,0 +1,114 @@
+use std::time::Duration;
+
+use tokio::time::Instant;
+
+pub const SUMMARY_INTERVAL: Duration = Duration::from_secs(10);
+
+pub struct Limiter {
+    rate: f64,
+    burst: f64,
+    tokens: f64,
+    updated: Instant,
+    suppressed: u64,
+    total: u64,
+}
+
+impl Limiter {
+    pub fn new(rate: f64, burst: u64, now: Instant) -> Limiter {
+        Limiter {
+            rate,
+            burst: burst.max(1) as f64,
+            tokens: burst.max(1) as f64,
+            updated: now,
+            suppressed: 0,
+            total: 0,
+        }
+    }
+
+    pub fn allow(&mut self, now: Instant) -> bool {
+        if self.rate <= 0.0 {
+            return true;
+        }
+
+        let elapsed = now.duration_since(self.updated).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.rate).min(self.burst);
+        self.updated = now;
+
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            return true;
+        }
+
+        self.suppressed += 1;
+        self.total += 1;
+        false
+    }
+
+    // lines suppressed since the last summary and in total
+    pub fn summarize(&mut self) -> Option<(u64, u64)> {
+        if self.suppressed == 0 {
+            return None;
+        }
+
+        let suppressed = self.suppressed;
+        self.suppressed = 0;
+        Some((suppressed, self.total))
+    }
+}
+
+#[cfg(test)]
+mod must {
+    use std::time::Duration;
+
+    use tokio::time::Instant;
+
+    use super::Limiter;
+
+    #[test]
+    fn allow_everything_without_rate() {
+        // arrange
+        let now = Instant::now();
+        let mut limiter = Limiter::new(0.0, 1, now);
+
+        // act
+        let allowed = (0..100).filter(|_| limiter.allow(now)).count();
+
+        // assert
+        assert_eq!(100, allowed);
+        assert_eq!(None, limiter.summarize());
+    }
+
+    #[test]
+    fn suppress_lines_beyond_burst() {
+        // arrange
+        let now = Instant::now();
+        let mut limiter = Limiter::new(1.0, 5, now);
+
+        // act
+        let allowed = (0..8).filter(|_| limiter.allow(now)).count();
+
+        // assert
+        assert_eq!(5, allowed);
+        assert_eq!(Some((3, 3)), limiter.summarize());
+        assert_eq!(None, limiter.summarize());
+    }
+
+    #[test]
+    fn refill_tokens_with_rate() {
+        // arrange
+        let now = Instant::now();
+        let mut limiter = Limiter::new(2.0, 2, now);
+        (0..3).for_each(|_| {
+            limiter.allow(now);
+        });
+        limiter.summarize();
+
+        // act
+        let later = now + Duration::from_secs(1);
+        let allowed = (0..3).filter(|_| limiter.allow(later)).count();
+
+        // assert
+        assert_eq!(2, allowed);
+        assert_eq!(Some((1, 2)), limiter.summarize());
+    }
+}
@@ -1,44 +1,58 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 use bollard::Docker;
 
-use tokio::{
-    sync::broadcast::{self, error::RecvError},
-    task::{self},
-};
-use tracing::error;
+use tokio::task::{self, JoinHandle};
 
-use crate::{configuration::Configuration, events::Event};
+use crate::{
+    bus::{Publisher, Subscriber},
+    reload::Reloads,
+    shutdown::{self, Cancellation},
+};
 
+mod alert;
 mod handle;
+mod limit;
+mod multiline;
+mod parse;
 mod stream;
 mod validate;
 
 pub async fn source(
-    receivers: Vec<broadcast::Receiver<Event>>,
-    event_sender: broadcast::Sender<Event>,
+    mut subscriber: Subscriber,
+    publisher: Publisher,
     client: Docker,
-    conf: &Configuration,
+    mut reloads: Reloads,
+    mut cancellation: Cancellation,
 ) {
-    if !conf.docker.stream_logs {
-        return;
-    }
-
-    let conf = conf.clone();
-    let (sender, mut receiver) = broadcast::channel::<Event>(500);
     task::spawn(async move {
-        let mut tasks = HashMap::new();
+        let mut conf = reloads.borrow().clone();
+        let mut tasks: HashMap<String, JoinHandle<()>> = HashMap::new();
+        let mut running = HashSet::new();
+
         loop {
-            match receiver.recv().await {
-                Ok(event) => handle::event(event, &mut tasks, &client, &event_sender, &conf).await,
-                Err(RecvError::Closed) => break,
-                Err(e) => {
-                    error!("receive failed: {}", e);
-                    continue;
+            tokio::select! {
+                event = subscriber.recv() => match event {
+                    Some(event) => {
+                        handle::event(event, &mut tasks, &mut running, &client, &publisher, &conf)
+                            .await
+                    }
+                    None => break,
+                },
+                Ok(()) = reloads.changed() => {
+                    let current = reloads.borrow().clone();
+                    handle::reload(&mut tasks, &running, &client, &publisher, &conf, &current)
+                        .await;
+                    conf = current;
+                }
+                _ = shutdown::cancelled(&mut cancellation) => {
+                    for (_, handle) in tasks.drain() {
+                        handle.abort();
+                    }
+
+                    break;
                 }
             }
         }
     });
-
-    super::join_receivers(receivers, sender).await;
 }
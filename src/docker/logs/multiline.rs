@@ -0,0 +1,189 @@
+use std::time::Duration;
+
+use regex::Regex;
+use tracing::warn;
+
+use crate::{configuration::Multiline, events::LogLine};
+
+pub struct Assembler {
+    continuation: Option<Regex>,
+    start: Option<Regex>,
+    timeout: Duration,
+    pending: Option<LogLine>,
+}
+
+impl Assembler {
+    pub fn new(conf: &Multiline) -> Option<Assembler> {
+        if conf.continuation.is_none() && conf.start.is_none() {
+            warn!("multiline rule requires a start or continuation pattern");
+            return None;
+        }
+
+        Some(Assembler {
+            continuation: compile(conf.continuation.as_deref())?,
+            start: compile(conf.start.as_deref())?,
+            timeout: Duration::from_millis(conf.timeout),
+            pending: None,
+        })
+    }
+
+    pub fn timeout(&self) -> Duration {
+        self.timeout
+    }
+
+    // returns the previous event once a line starts a new one
+    pub fn push(&mut self, line: LogLine) -> Option<LogLine> {
+        let continues = self.is_continuation(&line.message);
+        match &mut self.pending {
+            // frames of stdout and stderr never belong to the same event
+            Some(pending) if continues && pending.stream == line.stream => {
+                pending.message.push('\n');
+                pending.message.push_str(&line.message);
+                None
+            }
+            _ => self.pending.replace(line),
+        }
+    }
+
+    pub fn flush(&mut self) -> Option<LogLine> {
+        self.pending.take()
+    }
+
+    fn is_continuation(&self, message: &str) -> bool {
+        let starts = self.start.as_ref().is_some_and(|s| s.is_match(message));
+        let continues = self
+            .continuation
+            .as_ref()
+            .is_none_or(|c| c.is_match(message));
+
+        !starts && continues
+    }
+}
+
+fn compile(pattern: Option<&str>) -> Option<Option<Regex>> {
+    match pattern.map(Regex::new) {
+        Some(Ok(regex)) => Some(Some(regex)),
+        Some(Err(e)) => {
+            warn!("creating multiline rule (regex) failed: {}", e);
+            None
+        }
+        None => Some(None),
+    }
+}
+
+#[cfg(test)]
+mod must {
+    use crate::{
+        configuration::Multiline,
+        events::{LogLine, LogStream},
+    };
+
+    use super::Assembler;
+
+    fn create_assembler(start: Option<&str>, continuation: Option<&str>) -> Assembler {
+        Assembler::new(&Multiline {
+            continuation: continuation.map(|c| c.to_owned()),
+            start: start.map(|s| s.to_owned()),
+            timeout: 1000,
+        })
+        .unwrap()
+    }
+
+    fn create_line(message: &str, stream: LogStream, timestamp: Option<&str>) -> LogLine {
+        LogLine {
+            message: message.to_owned(),
+            stream,
+            timestamp: timestamp.map(|t| t.to_owned()),
+        }
+    }
+
+    fn message(line: Option<LogLine>) -> Option<String> {
+        line.map(|l| l.message)
+    }
+
+    #[test]
+    fn group_lines_until_next_start() {
+        // arrange
+        let mut assembler = create_assembler(Some(r"^\d{4}-"), None);
+
+        // act
+        let first = assembler.push(create_line("2021-08-10 error", LogStream::StdOut, None));
+        let second = assembler.push(create_line("  at Main.run", LogStream::StdOut, None));
+        let third = assembler.push(create_line("2021-08-10 next", LogStream::StdOut, None));
+
+        // assert
+        assert_eq!(None, first);
+        assert_eq!(None, second);
+        assert_eq!(
+            Some("2021-08-10 error\n  at Main.run".to_owned()),
+            message(third)
+        );
+        assert_eq!(
+            Some("2021-08-10 next".to_owned()),
+            message(assembler.flush())
+        );
+    }
+
+    #[test]
+    fn group_continuation_lines() {
+        // arrange
+        let mut assembler = create_assembler(None, Some(r"^\s+at "));
+
+        // act
+        assembler.push(create_line("Exception: boom", LogStream::StdErr, None));
+        assembler.push(create_line("    at Foo.bar", LogStream::StdErr, None));
+        let completed = assembler.push(create_line("done", LogStream::StdErr, None));
+
+        // assert
+        assert_eq!(
+            Some("Exception: boom\n    at Foo.bar".to_owned()),
+            message(completed)
+        );
+    }
+
+    #[test]
+    fn keep_timestamp_of_first_line() {
+        // arrange
+        let mut assembler = create_assembler(None, Some(r"^\s+at "));
+
+        // act
+        assembler.push(create_line("Exception", LogStream::StdErr, Some("t1")));
+        assembler.push(create_line("    at Foo.bar", LogStream::StdErr, Some("t2")));
+
+        // assert
+        assert_eq!(
+            Some(create_line(
+                "Exception\n    at Foo.bar",
+                LogStream::StdErr,
+                Some("t1")
+            )),
+            assembler.flush()
+        );
+    }
+
+    #[test]
+    fn separate_streams() {
+        // arrange
+        let mut assembler = create_assembler(None, Some(r"^\s+at "));
+
+        // act
+        assembler.push(create_line("Exception", LogStream::StdErr, None));
+        let completed = assembler.push(create_line("    at Foo.bar", LogStream::StdOut, None));
+
+        // assert
+        assert_eq!(Some("Exception".to_owned()), message(completed));
+    }
+
+    #[test]
+    fn reject_rule_without_patterns() {
+        // act
+        let assembler = Assembler::new(&Multiline {
+            continuation: None,
+            start: None,
+            timeout: 1000,
+        });
+
+        // assert
+        assert!(assembler.is_none());
+    }
+}
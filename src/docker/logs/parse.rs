@@ -0,0 +1,306 @@
+use regex::Regex;
+use serde_json::{json, Map, Value};
+use tracing::warn;
+
+use crate::configuration::{LogFormat, LogLevel, LogParser};
+
+const LEVEL_KEYS: [&str; 4] = ["level", "lvl", "severity", "log.level"];
+const MESSAGE_KEYS: [&str; 3] = ["message", "msg", "log"];
+
+pub struct Parser {
+    format: LogFormat,
+    level: Option<LogLevel>,
+    pattern: Option<Regex>,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct ParsedLog {
+    pub level: Option<LogLevel>,
+    pub message: String,
+    pub fields: Map<String, Value>,
+}
+
+impl Parser {
+    pub fn new(conf: &LogParser) -> Option<Parser> {
+        let pattern = match (conf.format, &conf.pattern) {
+            (LogFormat::Regex, Some(pattern)) => match Regex::new(pattern) {
+                Ok(regex) => Some(regex),
+                Err(e) => {
+                    warn!("creating log parser (regex) failed: {}", e);
+                    return None;
+                }
+            },
+            (LogFormat::Regex, None) => {
+                warn!("log parser with regex format requires a pattern");
+                return None;
+            }
+            _ => None,
+        };
+
+        Some(Parser {
+            format: conf.format,
+            level: conf.level,
+            pattern,
+        })
+    }
+
+    // returns None for lines below the configured level
+    pub fn parse(&self, line: &str) -> Option<ParsedLog> {
+        let line = line.trim_end();
+
+        let parsed = match self.format {
+            LogFormat::Json => parse_json(line),
+            LogFormat::Logfmt => parse_logfmt(line).map(into_parsed),
+            LogFormat::Regex => self.pattern.as_ref().and_then(|p| parse_regex(p, line)),
+        }
+        .unwrap_or_else(|| ParsedLog {
+            level: None,
+            message: line.to_owned(),
+            fields: Map::new(),
+        });
+
+        if let (Some(minimum), Some(level)) = (self.level, parsed.level) {
+            if level < minimum {
+                return None;
+            }
+        }
+
+        Some(parsed)
+    }
+}
+
+impl ParsedLog {
+    pub fn to_json(&self) -> String {
+        json!({
+            "level": self.level.map(get_level_name),
+            "message": self.message,
+            "fields": self.fields,
+        })
+        .to_string()
+    }
+}
+
+fn parse_json(line: &str) -> Option<ParsedLog> {
+    match serde_json::from_str::<Value>(line) {
+        Ok(Value::Object(fields)) => Some(into_parsed(fields)),
+        _ => None,
+    }
+}
+
+// plain text has no key=value pair, thus it is published unparsed
+fn parse_logfmt(line: &str) -> Option<Map<String, Value>> {
+    let mut fields = Map::new();
+    let mut chars = line.chars().peekable();
+    let mut paired = false;
+
+    loop {
+        while chars.next_if(|c| c.is_whitespace()).is_some() {}
+
+        let key: String =
+            std::iter::from_fn(|| chars.next_if(|c| *c != '=' && !c.is_whitespace())).collect();
+        if key.is_empty() {
+            break;
+        }
+
+        let mut value = String::new();
+        if chars.next_if_eq(&'=').is_some() {
+            paired = true;
+            if chars.next_if_eq(&'"').is_some() {
+                while let Some(c) = chars.next() {
+                    match c {
+                        '"' => break,
+                        '\\' => value.extend(chars.next()),
+                        _ => value.push(c),
+                    }
+                }
+            } else {
+                value = std::iter::from_fn(|| chars.next_if(|c| !c.is_whitespace())).collect();
+            }
+        }
+
+        fields.insert(key, Value::String(value));
+    }
+
+    match paired {
+        true => Some(fields),
+        false => None,
+    }
+}
+
+fn parse_regex(pattern: &Regex, line: &str) -> Option<ParsedLog> {
+    let captures = pattern.captures(line)?;
+
+    let mut fields = Map::new();
+    for name in pattern.capture_names().flatten() {
+        if let Some(value) = captures.name(name) {
+            fields.insert(name.to_owned(), Value::String(value.as_str().to_owned()));
+        }
+    }
+
+    Some(into_parsed(fields))
+}
+
+fn into_parsed(mut fields: Map<String, Value>) -> ParsedLog {
+    let level = take_first(&mut fields, &LEVEL_KEYS).and_then(|level| get_level(&level));
+    let message = take_first(&mut fields, &MESSAGE_KEYS).unwrap_or_default();
+
+    ParsedLog {
+        level,
+        message,
+        fields,
+    }
+}
+
+fn take_first(fields: &mut Map<String, Value>, keys: &[&str]) -> Option<String> {
+    keys.iter()
+        .find_map(|key| fields.remove(*key))
+        .map(|value| match value {
+            Value::String(value) => value,
+            value => value.to_string(),
+        })
+}
+
+fn get_level(level: &str) -> Option<LogLevel> {
+    match level.to_ascii_lowercase().as_str() {
+        "trace" => Some(LogLevel::Trace),
+        "debug" | "dbug" => Some(LogLevel::Debug),
+        "info" | "information" | "notice" => Some(LogLevel::Info),
+        "warn" | "warning" => Some(LogLevel::Warn),
+        "error" | "err" | "eror" => Some(LogLevel::Error),
+        "fatal" | "critical" | "crit" | "panic" => Some(LogLevel::Fatal),
+        _ => None,
+    }
+}
+
+fn get_level_name(level: LogLevel) -> &'static str {
+    match level {
+        LogLevel::Trace => "trace",
+        LogLevel::Debug => "debug",
+        LogLevel::Info => "info",
+        LogLevel::Warn => "warn",
+        LogLevel::Error => "error",
+        LogLevel::Fatal => "fatal",
+    }
+}
+
+#[cfg(test)]
+mod must {
+    use serde_json::{json, Map, Value};
+
+    use crate::configuration::{LogFormat, LogLevel, LogParser};
+
+    use super::{ParsedLog, Parser};
+
+    fn create_parser(format: LogFormat, level: Option<LogLevel>, pattern: Option<&str>) -> Parser {
+        Parser::new(&LogParser {
+            format,
+            level,
+            pattern: pattern.map(|p| p.to_owned()),
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn parse_json_line() {
+        // arrange
+        let parser = create_parser(LogFormat::Json, None, None);
+
+        // act
+        let parsed = parser.parse("{\"level\":\"WARN\",\"msg\":\"slow\",\"took\":12}\n");
+
+        // assert
+        let mut fields = Map::new();
+        fields.insert("took".to_owned(), json!(12));
+        assert_eq!(
+            Some(ParsedLog {
+                level: Some(LogLevel::Warn),
+                message: "slow".to_owned(),
+                fields,
+            }),
+            parsed
+        );
+    }
+
+    #[test]
+    fn parse_logfmt_line() {
+        // arrange
+        let parser = create_parser(LogFormat::Logfmt, None, None);
+
+        // act
+        let parsed = parser
+            .parse("level=error msg=\"connection \\\"db\\\" lost\" retry=3 dry")
+            .unwrap();
+
+        // assert
+        assert_eq!(Some(LogLevel::Error), parsed.level);
+        assert_eq!("connection \"db\" lost", parsed.message);
+        assert_eq!(Some(&json!("3")), parsed.fields.get("retry"));
+        assert_eq!(Some(&json!("")), parsed.fields.get("dry"));
+    }
+
+    #[test]
+    fn keep_plain_text_line_with_logfmt_format() {
+        // arrange
+        let parser = create_parser(LogFormat::Logfmt, None, None);
+
+        // act
+        let parsed = parser.parse("Starting server on port 8080").unwrap();
+
+        // assert
+        assert_eq!("Starting server on port 8080", parsed.message);
+        assert!(parsed.fields.is_empty());
+    }
+
+    #[test]
+    fn parse_regex_named_captures() {
+        // arrange
+        let parser = create_parser(
+            LogFormat::Regex,
+            None,
+            Some(r"^\[(?P<level>\w+)\] (?P<module>\w+): (?P<message>.*)$"),
+        );
+
+        // act
+        let parsed = parser.parse("[info] http: listening").unwrap();
+
+        // assert
+        assert_eq!(Some(LogLevel::Info), parsed.level);
+        assert_eq!("listening", parsed.message);
+        assert_eq!(Some(&json!("http")), parsed.fields.get("module"));
+    }
+
+    #[test]
+    fn filter_lines_below_level() {
+        // arrange
+        let parser = create_parser(LogFormat::Json, Some(LogLevel::Warn), None);
+
+        // act
+        let info = parser.parse("{\"level\":\"info\",\"msg\":\"a\"}");
+        let error = parser.parse("{\"level\":\"error\",\"msg\":\"b\"}");
+        let unknown = parser.parse("not json");
+
+        // assert
+        assert_eq!(None, info);
+        assert!(error.is_some());
+        assert_eq!("not json", unknown.unwrap().message);
+    }
+
+    #[test]
+    fn serialize_parsed_log() {
+        // arrange
+        let parsed = ParsedLog {
+            level: Some(LogLevel::Fatal),
+            message: "boom".to_owned(),
+            fields: Map::new(),
+        };
+
+        // act
+        let payload: Value = serde_json::from_str(&parsed.to_json()).unwrap();
+
+        // assert
+        assert_eq!(
+            json!({"level": "fatal", "message": "boom", "fields": {}}),
+            payload
+        );
+    }
+}
@@ -2,35 +2,172 @@ use bollard::{
     container::{LogOutput, LogsOptions},
     Docker,
 };
+use regex::Regex;
 use tokio::{
-    sync::broadcast,
     task::{self, JoinHandle},
+    time::{self, Instant},
 };
 use tokio_stream::StreamExt;
-use tracing::{error, warn};
+use tracing::warn;
 
 use crate::{
-    docker::logs::validate,
-    events::{Event, EventType},
+    bus::Publisher,
+    configuration::Configuration,
+    docker::logs::{
+        alert::Alerts,
+        limit::{Limiter, SUMMARY_INTERVAL},
+        multiline::Assembler,
+        parse::Parser,
+        validate,
+    },
+    events::{Event, EventType, LogLine, LogStream},
 };
 
+struct Output {
+    alerts: Option<Alerts>,
+    event: Event,
+    filters: Vec<Regex>,
+    limiter: Limiter,
+    parser: Option<Parser>,
+    publish: bool,
+    publisher: Publisher,
+    split: bool,
+}
+
+impl Output {
+    fn send(&mut self, mut line: LogLine) {
+        // alerts fire regardless of filters and rate limits
+        if let Some(alert) = self
+            .alerts
+            .as_mut()
+            .and_then(|alerts| alerts.check(&line, Instant::now()))
+        {
+            self.send_event(EventType::LogAlert(alert.clone()));
+            self.send_event(EventType::LastLogAlert(Some(alert)));
+        }
+
+        if !self.publish || !validate::log(&line.message, &self.filters) {
+            return;
+        }
+
+        if let Some(parser) = &self.parser {
+            match parser.parse(&line.message) {
+                Some(parsed) => line.message = parsed.to_json(),
+                None => return,
+            }
+        }
+
+        if self.limiter.allow(Instant::now()) {
+            self.publisher
+                .send(get_log_event(&self.event, line, self.split));
+        }
+    }
+
+    fn send_summary(&mut self) {
+        if let Some((suppressed, total)) = self.limiter.summarize() {
+            let line = LogLine {
+                message: format!("{} lines suppressed", suppressed),
+                stream: LogStream::StdErr,
+                timestamp: None,
+            };
+
+            self.publisher
+                .send(get_log_event(&self.event, line, self.split));
+            self.send_event(EventType::LogsSuppressed(total));
+        }
+    }
+
+    fn clear_alert(&mut self) {
+        if let Some(alerts) = &mut self.alerts {
+            alerts.clear();
+            self.send_event(EventType::LastLogAlert(None));
+        }
+    }
+
+    fn send_event(&self, event: EventType) {
+        self.publisher.send(Event {
+            container_name: self.event.container_name.to_owned(),
+            event,
+        });
+    }
+}
+
 pub async fn start(
     client: Docker,
     event: Event,
-    sender: broadcast::Sender<Event>,
+    publisher: Publisher,
+    conf: &Configuration,
 ) -> JoinHandle<()> {
+    let mut assembler = conf
+        .docker
+        .stream_logs_multiline
+        .get(&event.container_name)
+        .and_then(Assembler::new);
+    let mut output = Output {
+        alerts: conf
+            .docker
+            .stream_logs_alerts
+            .get(&event.container_name)
+            .map(|alerts| Alerts::new(alerts, conf.docker.stream_logs_alert_timeout)),
+        filters: validate::get_log_validation_regexes(conf),
+        limiter: Limiter::new(
+            conf.docker.stream_logs_rate,
+            conf.docker.stream_logs_burst,
+            Instant::now(),
+        ),
+        parser: conf
+            .docker
+            .stream_logs_parser
+            .get(&event.container_name)
+            .and_then(Parser::new),
+        publish: validate::publish(&event.container_name, conf),
+        event,
+        publisher,
+        split: conf.docker.stream_logs_split,
+    };
+
     task::spawn(async move {
-        let mut stream = client.logs(&event.container_name, Some(get_options()));
+        let mut stream = client.logs(&output.event.container_name, Some(get_options()));
+        let mut summary = time::interval(SUMMARY_INTERVAL);
+        let mut deadline: Option<Instant> = None;
 
-        while let Some(result) = stream.next().await {
-            match result {
-                Ok(logs) if validate::log(&logs) => {
-                    send_log_events(&event, &logs, &sender);
+        loop {
+            let expires = output.alerts.as_ref().and_then(Alerts::expires);
+
+            tokio::select! {
+                received = stream.next() => match received {
+                    Some(Ok(logs)) => {
+                        let line = get_log_line(&logs);
+                        match &mut assembler {
+                            Some(assembler) => {
+                                if let Some(line) = assembler.push(line) {
+                                    output.send(line);
+                                }
+                                deadline = Some(Instant::now() + assembler.timeout());
+                            }
+                            None => output.send(line),
+                        }
+                    }
+                    Some(Err(e)) => warn!("failed to receive valid logs: {}", e),
+                    None => break,
+                },
+                _ = time::sleep_until(deadline.unwrap_or_else(Instant::now)), if deadline.is_some() => {
+                    deadline = None;
+                    if let Some(line) = assembler.as_mut().and_then(Assembler::flush) {
+                        output.send(line);
+                    }
+                }
+                _ = summary.tick() => output.send_summary(),
+                _ = time::sleep_until(expires.unwrap_or_else(Instant::now)), if expires.is_some() => {
+                    output.clear_alert();
                 }
-                Ok(_) => {}
-                Err(e) => warn!("failed to receive valid stats: {}", e),
             }
         }
+
+        if let Some(line) = assembler.as_mut().and_then(Assembler::flush) {
+            output.send(line);
+        }
+        output.send_summary();
     })
 }
 
@@ -46,18 +183,103 @@ fn get_options() -> LogsOptions<String> {
     }
 }
 
-fn send_log_events(source: &Event, logs: &LogOutput, sender: &broadcast::Sender<Event>) {
-    match sender.send(get_log_event(source, logs)) {
-        Ok(_) => {}
-        Err(e) => {
-            error!("message was not sent: {}", e)
+fn get_log_line(logs: &LogOutput) -> LogLine {
+    let stream = match logs {
+        LogOutput::Console { .. } => LogStream::Console,
+        LogOutput::StdErr { .. } => LogStream::StdErr,
+        LogOutput::StdIn { .. } => LogStream::StdIn,
+        LogOutput::StdOut { .. } => LogStream::StdOut,
+    };
+
+    to_log_line(stream, &format!("{}", logs))
+}
+
+fn to_log_line(stream: LogStream, log: &str) -> LogLine {
+    let log = log.trim_end_matches(['\n', '\r']);
+
+    // docker prefixes every line with a RFC 3339 timestamp as requested by the options
+    let (timestamp, message) = match log.split_once(' ') {
+        Some((timestamp, message))
+            if timestamp.starts_with(|c: char| c.is_ascii_digit()) && timestamp.contains('T') =>
+        {
+            (Some(timestamp.to_owned()), message)
         }
+        _ => (None, log),
+    };
+
+    LogLine {
+        message: message.to_owned(),
+        stream,
+        timestamp,
     }
 }
 
-fn get_log_event(event: &Event, logs: &LogOutput) -> Event {
+fn get_log_event(event: &Event, line: LogLine, split: bool) -> Event {
+    let event_type = match (split, line.stream) {
+        (false, _) => EventType::Log(line),
+        (true, LogStream::StdErr) => EventType::LogStderr(line),
+        (true, _) => EventType::LogStdout(line),
+    };
+
     Event {
         container_name: event.container_name.to_owned(),
-        event: EventType::Log(format!("{}", logs)),
+        event: event_type,
+    }
+}
+
+#[cfg(test)]
+mod must {
+    use crate::events::{ContainerEvent, Event, EventType, LogLine, LogStream};
+
+    use super::{get_log_event, to_log_line};
+
+    #[test]
+    fn separate_timestamp_from_message() {
+        // act
+        let line = to_log_line(
+            LogStream::StdErr,
+            "2021-08-10T19:10:01.123456789Z connection refused\n",
+        );
+
+        // assert
+        assert_eq!(
+            LogLine {
+                message: "connection refused".to_owned(),
+                stream: LogStream::StdErr,
+                timestamp: Some("2021-08-10T19:10:01.123456789Z".to_owned()),
+            },
+            line
+        );
+    }
+
+    #[test]
+    fn keep_message_without_timestamp() {
+        // act
+        let line = to_log_line(LogStream::StdOut, "plain text");
+
+        // assert
+        assert_eq!(None, line.timestamp);
+        assert_eq!("plain text", line.message);
+    }
+
+    #[test]
+    fn route_streams_when_split() {
+        // arrange
+        let source = Event {
+            container_name: "borg".to_owned(),
+            event: EventType::State(ContainerEvent::Start),
+        };
+        let line = LogLine {
+            stream: LogStream::StdErr,
+            ..Default::default()
+        };
+
+        // act
+        let combined = get_log_event(&source, line.clone(), false);
+        let split = get_log_event(&source, line, true);
+
+        // assert
+        assert_eq!("logs", combined.event.to_string());
+        assert_eq!("logs_stderr", split.event.to_string());
     }
 }
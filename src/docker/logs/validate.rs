@@ -1,16 +1,14 @@
-use bollard::{container::LogOutput, Docker};
-use lazy_static::lazy_static;
+use bollard::Docker;
 use regex::Regex;
 use tracing::warn;
 
 use crate::{configuration::Configuration, docker::container, events::Event};
 
 pub async fn target(event: &Event, client: &Docker, conf: &Configuration) -> bool {
-    let container;
-    match container::get_by_name(client, &event.container_name).await {
-        Some(c) => container = c,
+    let container = match container::get_by_name(client, &event.container_name).await {
+        Some(c) => c,
         None => return false,
-    }
+    };
 
     // docker2mqtt should not stream his own logs generating logs streaming his on logs gene..
     if let Some(image) = &container.image {
@@ -20,34 +18,24 @@ pub async fn target(event: &Event, client: &Docker, conf: &Configuration) -> boo
     }
 
     let container_name = container::get_name(&container);
-    if conf
-        .docker
-        .stream_logs_container
-        .iter()
-        .any(|name| name.eq_ignore_ascii_case(container_name))
-    {
-        return true;
-    }
-
-    false
+    publish(container_name, conf) || conf.docker.stream_logs_alerts.contains_key(container_name)
 }
 
-pub fn log(logs: &LogOutput) -> bool {
-    lazy_static! {
-        static ref LOG_VALIDATORS: Vec<Regex> = get_log_validation_regexes();
-    }
+// alert rules stream logs without publishing them
+pub fn publish(container_name: &str, conf: &Configuration) -> bool {
+    conf.docker.publishes_logs(container_name)
+}
 
-    let log = format!("{}", logs);
-    for rgx in LOG_VALIDATORS.iter() {
-        if rgx.is_match(&log) {
+pub fn log(log: &str, validators: &[Regex]) -> bool {
+    for rgx in validators.iter() {
+        if rgx.is_match(log) {
             return true;
         }
     }
     false
 }
 
-fn get_log_validation_regexes() -> Vec<Regex> {
-    let conf = Configuration::new();
+pub fn get_log_validation_regexes(conf: &Configuration) -> Vec<Regex> {
     let mut validators = vec![];
     for rgx in conf.docker.stream_logs_filter.iter() {
         match Regex::new(rgx) {
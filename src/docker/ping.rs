@@ -0,0 +1,28 @@
+use std::time::Duration;
+
+use bollard::Docker;
+use tokio::{task, time};
+
+use crate::{
+    configuration::Configuration,
+    health,
+    shutdown::{self, Cancellation},
+};
+
+const PING_INTERVAL: Duration = Duration::from_secs(10);
+
+pub async fn task(client: Docker, conf: &Configuration, mut cancellation: Cancellation) {
+    if conf.http.is_none() {
+        return;
+    }
+
+    task::spawn(async move {
+        let mut interval = time::interval(PING_INTERVAL);
+        loop {
+            tokio::select! {
+                _ = interval.tick() => health::set_docker_reachable(client.ping().await.is_ok()),
+                _ = shutdown::cancelled(&mut cancellation) => break,
+            }
+        }
+    });
+}
@@ -5,51 +5,84 @@ use bollard::{
     Docker,
 };
 use tokio::{
-    sync::broadcast::{self, error::RecvError},
     task::{self, JoinHandle},
+    time::Instant,
 };
 use tokio_stream::StreamExt;
 use tracing::error;
 
-use crate::events::{ContainerEvent, Event, EventType};
+use crate::{
+    bus::{Publisher, Subscriber},
+    configuration::Configuration,
+    events::{ContainerEvent, Event, EventType},
+    metrics,
+    reload::Reloads,
+    shutdown::{self, Cancellation},
+};
+
+use self::{rolling::Rolling, sampler::Sampler};
 
 mod cpu;
 mod memory;
+mod rolling;
+mod sampler;
 
 pub async fn source(
-    receivers: Vec<broadcast::Receiver<Event>>,
-    event_sender: broadcast::Sender<Event>,
+    mut subscriber: Subscriber,
+    publisher: Publisher,
     client: Docker,
+    mut reloads: Reloads,
+    mut cancellation: Cancellation,
 ) {
-    let (sender, mut receiver) = broadcast::channel(500);
     task::spawn(async move {
-        let mut tasks = HashMap::new();
+        let mut conf = reloads.borrow().clone();
+        let mut tasks: HashMap<String, JoinHandle<()>> = HashMap::new();
+
         loop {
-            match receiver.recv().await {
-                Ok(event) => handle_event(event, &mut tasks, &client, &event_sender).await,
-                Err(RecvError::Closed) => break,
-                Err(e) => {
-                    error!("receive failed: {}", e);
-                    continue;
+            tokio::select! {
+                event = subscriber.recv() => match event {
+                    Some(event) => handle_event(event, &mut tasks, &client, &publisher, &conf).await,
+                    None => break,
+                },
+                Ok(()) = reloads.changed() => {
+                    let current = reloads.borrow().clone();
+                    handle_reload(&mut tasks, &client, &publisher, &conf, &current).await;
+                    conf = current;
+                }
+                _ = shutdown::cancelled(&mut cancellation) => {
+                    for (_, handle) in tasks.drain() {
+                        handle.abort();
+                    }
+
+                    break;
                 }
             }
         }
     });
-
-    super::join_receivers(receivers, sender).await;
 }
 
 async fn handle_event(
     event: Event,
     tasks: &mut HashMap<String, JoinHandle<()>>,
     client: &Docker,
-    event_sender: &broadcast::Sender<Event>,
+    publisher: &Publisher,
+    conf: &Configuration,
 ) {
     match &event.event {
         EventType::State(ContainerEvent::Start) => {
+            let sampler = Sampler::new(conf.docker.get_stats_settings(&event.container_name));
+            let rolling = Rolling::new(conf.docker.stats_windows.clone());
+
             tasks.insert(
                 event.container_name.to_owned(),
-                start_stats_stream(client.clone(), event.clone(), event_sender.clone()).await,
+                start_stats_stream(
+                    client.clone(),
+                    event.clone(),
+                    sampler,
+                    rolling,
+                    publisher.clone(),
+                )
+                .await,
             );
         }
         EventType::State(ContainerEvent::Stop) => {
@@ -60,12 +93,43 @@ async fn handle_event(
         }
         _ => {}
     }
+
+    metrics::set_stats_streams(tasks.len());
+}
+
+// restarts streams of running containers whose sampling settings changed
+async fn handle_reload(
+    tasks: &mut HashMap<String, JoinHandle<()>>,
+    client: &Docker,
+    publisher: &Publisher,
+    previous: &Configuration,
+    conf: &Configuration,
+) {
+    let container_names: Vec<String> = tasks.keys().cloned().collect();
+    for container_name in container_names {
+        if previous.docker.get_stats_settings(&container_name)
+            == conf.docker.get_stats_settings(&container_name)
+            && previous.docker.stats_windows == conf.docker.stats_windows
+        {
+            continue;
+        }
+
+        let event = Event {
+            container_name,
+            event: EventType::State(ContainerEvent::Start),
+        };
+
+        stop_stats_stream(tasks, &event);
+        handle_event(event, tasks, client, publisher, conf).await;
+    }
 }
 
 async fn start_stats_stream(
     client: Docker,
     event: Event,
-    sender: broadcast::Sender<Event>,
+    mut sampler: Sampler,
+    mut rolling: Rolling,
+    publisher: Publisher,
 ) -> JoinHandle<()> {
     task::spawn(async move {
         let mut stream = client.stats(
@@ -77,7 +141,9 @@ async fn start_stats_stream(
         );
         while let Some(result) = stream.next().await {
             match result {
-                Ok(stats) => send_stat_events(&event, &stats, &sender),
+                Ok(stats) => {
+                    send_stat_events(&event, &stats, &mut sampler, &mut rolling, &publisher)
+                }
                 Err(e) => error!("failed to receive valid stats: {}", e),
             }
         }
@@ -90,26 +156,24 @@ fn stop_stats_stream(tasks: &mut HashMap<String, task::JoinHandle<()>>, event: &
     }
 }
 
-fn send_stat_events(source: &Event, stats: &Stats, sender: &broadcast::Sender<Event>) {
-    for event in get_stat_events(source, stats).into_iter() {
-        match sender.send(event) {
-            Ok(_) => {}
-            Err(e) => {
-                error!("message was not sent: {}", e)
-            }
-        }
-    }
-}
+fn send_stat_events(
+    source: &Event,
+    stats: &Stats,
+    sampler: &mut Sampler,
+    rolling: &mut Rolling,
+    publisher: &Publisher,
+) {
+    let cpu = cpu::usage(&stats.precpu_stats, &stats.cpu_stats);
+    let memory = memory::usage(&stats.memory_stats);
+    let now = Instant::now();
+
+    let mut events = sampler.add(cpu, memory, now);
+    events.extend(rolling.add(cpu, memory, now));
 
-fn get_stat_events(event: &Event, stats: &Stats) -> Vec<Event> {
-    vec![
-        Event {
-            container_name: event.container_name.to_owned(),
-            event: EventType::CpuUsage(cpu::usage(&stats.precpu_stats, &stats.cpu_stats)),
-        },
-        Event {
-            container_name: event.container_name.to_owned(),
-            event: EventType::MemoryUsage(memory::usage(&stats.memory_stats)),
-        },
-    ]
+    for event in events.into_iter() {
+        publisher.send(Event {
+            container_name: source.container_name.to_owned(),
+            event,
+        });
+    }
 }
@@ -0,0 +1,200 @@
+use std::{collections::VecDeque, time::Duration};
+
+use tokio::time::Instant;
+
+use crate::events::{EventType, Statistics};
+
+const PUBLISH_INTERVAL: Duration = Duration::from_secs(60);
+
+struct Sample {
+    time: Instant,
+    cpu: f64,
+    memory: f64,
+}
+
+pub struct Rolling {
+    windows: Vec<u64>,
+    samples: VecDeque<Sample>,
+    published: Option<Instant>,
+}
+
+impl Rolling {
+    pub fn new(windows: Vec<u64>) -> Rolling {
+        Rolling {
+            windows,
+            samples: VecDeque::new(),
+            published: None,
+        }
+    }
+
+    pub fn add(&mut self, cpu: f64, memory: f64, now: Instant) -> Vec<EventType> {
+        let longest = match self.windows.iter().max() {
+            Some(longest) => Duration::from_secs(*longest),
+            None => return vec![],
+        };
+
+        self.samples.push_back(Sample {
+            time: now,
+            cpu,
+            memory,
+        });
+
+        while let Some(sample) = self.samples.front() {
+            if now.duration_since(sample.time) <= longest {
+                break;
+            }
+
+            self.samples.pop_front();
+        }
+
+        let published = *self.published.get_or_insert(now);
+        if now.duration_since(published) < PUBLISH_INTERVAL {
+            return vec![];
+        }
+
+        self.published = Some(now);
+
+        vec![
+            EventType::CpuStatistics(self.get_statistics(now, |sample| sample.cpu)),
+            EventType::MemoryStatistics(self.get_statistics(now, |sample| sample.memory)),
+        ]
+    }
+
+    fn get_statistics(&self, now: Instant, value: fn(&Sample) -> f64) -> Vec<Statistics> {
+        self.windows
+            .iter()
+            .map(|window| {
+                let values: Vec<f64> = self
+                    .samples
+                    .iter()
+                    .filter(|sample| now.duration_since(sample.time).as_secs() <= *window)
+                    .map(value)
+                    .collect();
+
+                get_window_statistics(*window, values)
+            })
+            .collect()
+    }
+}
+
+fn get_window_statistics(window: u64, mut values: Vec<f64>) -> Statistics {
+    if values.is_empty() {
+        return Statistics {
+            window,
+            ..Default::default()
+        };
+    }
+
+    values.sort_by(|a, b| a.total_cmp(b));
+
+    // nearest rank percentile
+    let rank = (values.len() as f64 * 0.95).ceil() as usize;
+
+    Statistics {
+        window,
+        min: values[0],
+        avg: values.iter().sum::<f64>() / values.len() as f64,
+        max: values[values.len() - 1],
+        p95: values[rank.max(1) - 1],
+    }
+}
+
+#[cfg(test)]
+mod must {
+    use std::time::Duration;
+
+    use tokio::time::Instant;
+
+    use crate::events::{EventType, Statistics};
+
+    use super::Rolling;
+
+    #[test]
+    fn not_publish_without_windows() {
+        // arrange
+        let mut rolling = Rolling::new(vec![]);
+        let now = Instant::now();
+
+        // act
+        rolling.add(1.0, 1.0, now);
+        let events = rolling.add(1.0, 1.0, now + Duration::from_secs(60));
+
+        // assert
+        assert!(events.is_empty());
+    }
+
+    #[test]
+    fn publish_statistics_once_a_minute() {
+        // arrange
+        let mut rolling = Rolling::new(vec![60]);
+        let now = Instant::now();
+
+        // act
+        let first = rolling.add(1.0, 1.0, now);
+        let second = rolling.add(1.0, 1.0, now + Duration::from_secs(30));
+        let third = rolling.add(1.0, 1.0, now + Duration::from_secs(60));
+
+        // assert
+        assert!(first.is_empty());
+        assert!(second.is_empty());
+        assert_eq!(2, third.len());
+    }
+
+    #[test]
+    fn compute_statistics_per_window() {
+        // arrange
+        let mut rolling = Rolling::new(vec![30, 120]);
+        let now = Instant::now();
+        for (seconds, value) in [(0, 8.0), (20, 2.0), (40, 4.0), (50, 6.0)] {
+            rolling.add(value, value * 10.0, now + Duration::from_secs(seconds));
+        }
+
+        // act
+        let events = rolling.add(1.0, 10.0, now + Duration::from_secs(60));
+
+        // assert
+        assert_eq!(
+            EventType::CpuStatistics(vec![
+                Statistics {
+                    window: 30,
+                    min: 1.0,
+                    avg: 11.0 / 3.0,
+                    max: 6.0,
+                    p95: 6.0,
+                },
+                Statistics {
+                    window: 120,
+                    min: 1.0,
+                    avg: 4.2,
+                    max: 8.0,
+                    p95: 8.0,
+                },
+            ]),
+            events[0]
+        );
+    }
+
+    #[test]
+    fn evict_samples_beyond_longest_window() {
+        // arrange
+        let mut rolling = Rolling::new(vec![60]);
+        let now = Instant::now();
+        rolling.add(100.0, 100.0, now);
+
+        // act
+        rolling.add(1.0, 1.0, now + Duration::from_secs(61));
+        let events = rolling.add(3.0, 3.0, now + Duration::from_secs(121));
+
+        // assert
+        assert_eq!(
+            EventType::MemoryStatistics(vec![Statistics {
+                window: 60,
+                min: 1.0,
+                avg: 2.0,
+                max: 3.0,
+                p95: 3.0,
+            }]),
+            events[1]
+        );
+    }
+}
@@ -0,0 +1,187 @@
+use std::time::Duration;
+
+use tokio::time::Instant;
+
+use crate::{
+    configuration::{Aggregation, StatsSettings},
+    events::EventType,
+};
+
+#[derive(Default)]
+struct Window {
+    count: u32,
+    max: f64,
+    sum: f64,
+}
+
+impl Window {
+    fn add(&mut self, value: f64) {
+        if self.count == 0 || value > self.max {
+            self.max = value;
+        }
+
+        self.count += 1;
+        self.sum += value;
+    }
+
+    fn get(&self, aggregation: Aggregation) -> f64 {
+        match aggregation {
+            Aggregation::Average => self.sum / self.count as f64,
+            Aggregation::Max => self.max,
+        }
+    }
+}
+
+pub struct Sampler {
+    settings: StatsSettings,
+    started: Option<Instant>,
+    cpu: Window,
+    memory: Window,
+    published_cpu: Option<f64>,
+    published_memory: Option<f64>,
+}
+
+impl Sampler {
+    pub fn new(settings: StatsSettings) -> Sampler {
+        Sampler {
+            settings,
+            started: None,
+            cpu: Window::default(),
+            memory: Window::default(),
+            published_cpu: None,
+            published_memory: None,
+        }
+    }
+
+    pub fn add(&mut self, cpu: f64, memory: f64, now: Instant) -> Vec<EventType> {
+        let started = *self.started.get_or_insert(now);
+
+        self.cpu.add(cpu);
+        self.memory.add(memory);
+
+        if now.duration_since(started) < Duration::from_secs(self.settings.interval) {
+            return vec![];
+        }
+
+        let cpu = self.cpu.get(self.settings.aggregation);
+        let memory = self.memory.get(self.settings.aggregation);
+
+        self.started = None;
+        self.cpu = Window::default();
+        self.memory = Window::default();
+
+        let mut result = vec![];
+        if exceeds_deadband(self.published_cpu, cpu, self.settings.deadband) {
+            self.published_cpu = Some(cpu);
+            result.push(EventType::CpuUsage(cpu));
+        }
+
+        if exceeds_deadband(self.published_memory, memory, self.settings.deadband) {
+            self.published_memory = Some(memory);
+            result.push(EventType::MemoryUsage(memory));
+        }
+
+        result
+    }
+}
+
+fn exceeds_deadband(published: Option<f64>, value: f64, deadband: f64) -> bool {
+    match published {
+        Some(published) => (value - published).abs() > deadband,
+        None => true,
+    }
+}
+
+#[cfg(test)]
+mod must {
+    use std::time::Duration;
+
+    use tokio::time::Instant;
+
+    use crate::{
+        configuration::{Aggregation, StatsSettings},
+        events::EventType,
+    };
+
+    use super::Sampler;
+
+    fn create_sampler(aggregation: Aggregation, deadband: f64, interval: u64) -> Sampler {
+        Sampler::new(StatsSettings {
+            aggregation,
+            deadband,
+            interval,
+        })
+    }
+
+    #[test]
+    fn publish_every_sample_without_interval() {
+        // arrange
+        let mut sampler = create_sampler(Aggregation::Average, 0.0, 0);
+        let now = Instant::now();
+
+        // act
+        let first = sampler.add(1.0, 2.0, now);
+        let second = sampler.add(3.0, 2.0, now + Duration::from_secs(1));
+
+        // assert
+        assert_eq!(
+            vec![EventType::CpuUsage(1.0), EventType::MemoryUsage(2.0)],
+            first
+        );
+        assert_eq!(vec![EventType::CpuUsage(3.0)], second);
+    }
+
+    #[test]
+    fn average_samples_within_interval() {
+        // arrange
+        let mut sampler = create_sampler(Aggregation::Average, 0.0, 10);
+        let now = Instant::now();
+
+        // act
+        let first = sampler.add(1.0, 4.0, now);
+        let second = sampler.add(2.0, 4.0, now + Duration::from_secs(5));
+        let third = sampler.add(6.0, 7.0, now + Duration::from_secs(10));
+
+        // assert
+        assert!(first.is_empty());
+        assert!(second.is_empty());
+        assert_eq!(
+            vec![EventType::CpuUsage(3.0), EventType::MemoryUsage(5.0)],
+            third
+        );
+    }
+
+    #[test]
+    fn publish_maximum_within_interval() {
+        // arrange
+        let mut sampler = create_sampler(Aggregation::Max, 0.0, 2);
+        let now = Instant::now();
+
+        // act
+        sampler.add(5.0, 1.0, now);
+        sampler.add(9.0, 3.0, now + Duration::from_secs(1));
+        let events = sampler.add(2.0, 2.0, now + Duration::from_secs(2));
+
+        // assert
+        assert_eq!(
+            vec![EventType::CpuUsage(9.0), EventType::MemoryUsage(3.0)],
+            events
+        );
+    }
+
+    #[test]
+    fn suppress_changes_within_deadband() {
+        // arrange
+        let mut sampler = create_sampler(Aggregation::Average, 1.0, 0);
+        let now = Instant::now();
+        sampler.add(10.0, 10.0, now);
+
+        // act
+        let within = sampler.add(10.5, 9.5, now);
+        let beyond = sampler.add(11.5, 9.5, now);
+
+        // assert
+        assert!(within.is_empty());
+        assert_eq!(vec![EventType::CpuUsage(11.5)], beyond);
+    }
+}
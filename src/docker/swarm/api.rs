@@ -0,0 +1,69 @@
+use std::fmt;
+
+use bollard::models::{Node, Task};
+use hyper::{Body, Client, StatusCode};
+use hyperlocal::{UnixClientExt, UnixConnector, Uri};
+use serde::de::DeserializeOwned;
+
+use crate::docker::client;
+
+pub type ApiResult<T> = Result<T, ApiErr>;
+
+#[derive(Debug)]
+pub enum ApiErr {
+    Http(hyper::Error),
+    InvalidResponse(serde_json::Error),
+    Status(StatusCode),
+}
+
+impl fmt::Display for ApiErr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ApiErr::Http(e) => write!(f, "request failed: {}", e),
+            ApiErr::InvalidResponse(e) => write!(f, "invalid response: {}", e),
+            ApiErr::Status(status) => write!(f, "unexpected status {}", status),
+        }
+    }
+}
+
+#[derive(Clone)]
+// bollard does not cover the task and node endpoints, thus they are requested on the socket directly
+pub struct Api {
+    client: Client<UnixConnector, Body>,
+    socket: String,
+}
+
+impl Api {
+    pub fn new() -> Api {
+        Api {
+            client: Client::unix(),
+            socket: client::get_socket(),
+        }
+    }
+
+    pub async fn tasks(&self) -> ApiResult<Vec<Task>> {
+        self.get("/tasks").await
+    }
+
+    pub async fn nodes(&self) -> ApiResult<Vec<Node>> {
+        self.get("/nodes").await
+    }
+
+    async fn get<T: DeserializeOwned>(&self, path: &str) -> ApiResult<T> {
+        let response = self
+            .client
+            .get(Uri::new(&self.socket, path).into())
+            .await
+            .map_err(ApiErr::Http)?;
+
+        if !response.status().is_success() {
+            return Err(ApiErr::Status(response.status()));
+        }
+
+        let body = hyper::body::to_bytes(response.into_body())
+            .await
+            .map_err(ApiErr::Http)?;
+
+        serde_json::from_slice(&body).map_err(ApiErr::InvalidResponse)
+    }
+}
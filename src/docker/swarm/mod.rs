@@ -0,0 +1,110 @@
+use std::{collections::HashMap, time::Duration};
+
+use bollard::{service::ListServicesOptions, system::EventsOptions, Docker};
+use tokio::{
+    task,
+    time::{self, Instant},
+};
+use tokio_stream::StreamExt;
+use tracing::warn;
+
+use crate::{
+    bus::Publisher,
+    configuration::Configuration,
+    events::{ContainerEvent, Event, EventType, HOST},
+    shutdown::{self, Cancellation},
+};
+
+use self::{api::Api, state::SwarmState};
+
+mod api;
+mod state;
+
+const SWARM_EVENT_DELAY: Duration = Duration::from_secs(1);
+
+pub async fn source(
+    publisher: Publisher,
+    client: Docker,
+    conf: &Configuration,
+    mut cancellation: Cancellation,
+) {
+    let swarm = match &conf.swarm {
+        Some(swarm) => swarm.clone(),
+        None => return,
+    };
+
+    task::spawn(async move {
+        publisher.send(Event {
+            container_name: HOST.to_owned(),
+            event: EventType::State(ContainerEvent::Create),
+        });
+
+        let api = Api::new();
+        let mut state = SwarmState::default();
+
+        let mut swarm_events = client.events(Some(get_options()));
+        let mut interval = time::interval(Duration::from_secs(swarm.interval));
+        let mut deadline: Option<Instant> = None;
+
+        loop {
+            tokio::select! {
+                _ = interval.tick() => refresh(&client, &api, &mut state, &publisher).await,
+                Some(_) = swarm_events.next() => {
+                    deadline.get_or_insert_with(|| Instant::now() + SWARM_EVENT_DELAY);
+                }
+                _ = time::sleep_until(deadline.unwrap_or_else(Instant::now)), if deadline.is_some() => {
+                    deadline = None;
+                    refresh(&client, &api, &mut state, &publisher).await;
+                }
+                _ = shutdown::cancelled(&mut cancellation) => break,
+            }
+        }
+    });
+}
+
+fn get_options() -> EventsOptions<String> {
+    let mut query = HashMap::new();
+    query.insert(
+        "type".to_owned(),
+        vec!["service".to_owned(), "node".to_owned()],
+    );
+
+    EventsOptions::<String> {
+        since: None,
+        until: None,
+        filters: query,
+    }
+}
+
+async fn refresh(client: &Docker, api: &Api, state: &mut SwarmState, publisher: &Publisher) {
+    let services = match client
+        .list_services(None::<ListServicesOptions<String>>)
+        .await
+    {
+        Ok(services) => services,
+        Err(e) => {
+            warn!("could not resolve swarm services: {}", e);
+            return;
+        }
+    };
+
+    let tasks = match api.tasks().await {
+        Ok(tasks) => tasks,
+        Err(e) => {
+            warn!("could not resolve swarm tasks: {}", e);
+            return;
+        }
+    };
+
+    let nodes = match api.nodes().await {
+        Ok(nodes) => nodes,
+        Err(e) => {
+            warn!("could not resolve swarm nodes: {}", e);
+            return;
+        }
+    };
+
+    for event in state.update(&services, &tasks, &nodes) {
+        publisher.send(event);
+    }
+}
@@ -0,0 +1,396 @@
+use std::collections::HashMap;
+
+use bollard::models::{Node, Service, Task, TaskState};
+
+use crate::events::{self, ContainerEvent, Event, EventType, SwarmNode, TaskTransition, HOST};
+
+#[derive(Clone, Debug, Default, PartialEq)]
+struct Replicas {
+    desired: u64,
+    running: u64,
+}
+
+#[derive(Default)]
+pub struct SwarmState {
+    services: HashMap<String, Replicas>,
+    tasks: HashMap<String, String>,
+    nodes: Vec<SwarmNode>,
+}
+
+impl SwarmState {
+    pub fn update(&mut self, services: &[Service], tasks: &[Task], nodes: &[Node]) -> Vec<Event> {
+        let mut result = vec![];
+
+        let node_names = get_node_names(nodes);
+        let swarm_nodes = get_swarm_nodes(nodes);
+        if swarm_nodes != self.nodes {
+            self.nodes = swarm_nodes;
+            result.push(Event {
+                container_name: HOST.to_owned(),
+                event: EventType::SwarmNodes(self.nodes.to_owned()),
+            });
+        }
+
+        let service_names: HashMap<String, String> = services
+            .iter()
+            .filter_map(|service| Some((service.id.to_owned()?, get_service_name(service)?)))
+            .collect();
+
+        let mut current_services = HashMap::new();
+        for service in services {
+            let (id, name) = match (&service.id, get_service_name(service)) {
+                (Some(id), Some(name)) => (id, name),
+                _ => continue,
+            };
+
+            let replicas = get_replicas(service, id, tasks);
+            let container_name = events::service_name(&name);
+            let previous = self.services.get(&name);
+
+            if previous.is_none() {
+                result.push(create_event(
+                    &container_name,
+                    EventType::State(ContainerEvent::Create),
+                ));
+            }
+
+            if previous.map(|p| p.desired) != Some(replicas.desired) {
+                result.push(create_event(
+                    &container_name,
+                    EventType::DesiredReplicas(replicas.desired),
+                ));
+            }
+
+            if previous.map(|p| p.running) != Some(replicas.running) {
+                result.push(create_event(
+                    &container_name,
+                    EventType::RunningReplicas(replicas.running),
+                ));
+            }
+
+            current_services.insert(name, replicas);
+        }
+
+        let mut removed: Vec<&String> = self
+            .services
+            .keys()
+            .filter(|name| !current_services.contains_key(*name))
+            .collect();
+        removed.sort();
+
+        for name in removed {
+            result.push(create_event(
+                &events::service_name(name),
+                EventType::State(ContainerEvent::Destroy),
+            ));
+        }
+
+        self.services = current_services;
+
+        let mut tasks: Vec<&Task> = tasks.iter().collect();
+        tasks.sort_by_key(|task| task.updated_at);
+
+        let mut current_tasks = HashMap::new();
+        for task in tasks {
+            let (id, state) = match (&task.id, get_task_state(task)) {
+                (Some(id), Some(state)) => (id, state.to_string()),
+                _ => continue,
+            };
+
+            let service_name = match task.service_id.as_ref().and_then(|s| service_names.get(s)) {
+                Some(service_name) => service_name,
+                None => continue,
+            };
+
+            let previous_state = self.tasks.get(id).cloned();
+            let current = task.desired_state == Some(TaskState::RUNNING);
+            if previous_state.as_ref() != Some(&state) && (previous_state.is_some() || current) {
+                let node = task
+                    .node_id
+                    .as_ref()
+                    .and_then(|node_id| node_names.get(node_id))
+                    .cloned()
+                    .unwrap_or_default();
+
+                result.push(create_event(
+                    &events::service_name(service_name),
+                    EventType::TaskState(TaskTransition {
+                        task: get_task_name(service_name, task),
+                        node,
+                        previous_state,
+                        state: state.to_owned(),
+                    }),
+                ));
+            }
+
+            current_tasks.insert(id.to_owned(), state);
+        }
+
+        self.tasks = current_tasks;
+
+        result
+    }
+}
+
+fn get_service_name(service: &Service) -> Option<String> {
+    service.spec.as_ref().and_then(|spec| spec.name.to_owned())
+}
+
+fn get_replicas(service: &Service, service_id: &str, tasks: &[Task]) -> Replicas {
+    let service_tasks: Vec<&Task> = tasks
+        .iter()
+        .filter(|task| task.service_id.as_deref() == Some(service_id))
+        .collect();
+
+    let replicated = service
+        .spec
+        .as_ref()
+        .and_then(|spec| spec.mode.as_ref())
+        .and_then(|mode| mode.replicated.as_ref())
+        .and_then(|replicated| replicated.replicas);
+
+    let desired = match replicated {
+        Some(replicas) => replicas.max(0) as u64,
+        None => service_tasks
+            .iter()
+            .filter(|task| task.desired_state == Some(TaskState::RUNNING))
+            .count() as u64,
+    };
+
+    let running = service_tasks
+        .iter()
+        .filter(|task| get_task_state(task) == Some(&TaskState::RUNNING))
+        .count() as u64;
+
+    Replicas { desired, running }
+}
+
+fn get_task_state(task: &Task) -> Option<&TaskState> {
+    task.status
+        .as_ref()
+        .and_then(|status| status.state.as_ref())
+}
+
+fn get_task_name(service_name: &str, task: &Task) -> String {
+    match (&task.slot, &task.node_id) {
+        (Some(slot), _) => format!("{}.{}", service_name, slot),
+        (None, Some(node_id)) => format!("{}.{}", service_name, node_id),
+        (None, None) => service_name.to_owned(),
+    }
+}
+
+fn get_node_names(nodes: &[Node]) -> HashMap<String, String> {
+    nodes
+        .iter()
+        .filter_map(|node| Some((node.id.to_owned()?, get_node_name(node))))
+        .collect()
+}
+
+fn get_node_name(node: &Node) -> String {
+    node.description
+        .as_ref()
+        .and_then(|description| description.hostname.to_owned())
+        .or_else(|| node.id.to_owned())
+        .unwrap_or_default()
+}
+
+fn get_swarm_nodes(nodes: &[Node]) -> Vec<SwarmNode> {
+    let mut result: Vec<SwarmNode> = nodes
+        .iter()
+        .map(|node| SwarmNode {
+            name: get_node_name(node),
+            state: node
+                .status
+                .as_ref()
+                .and_then(|status| status.state.as_ref())
+                .map(|state| state.to_string())
+                .unwrap_or_default(),
+            availability: node
+                .spec
+                .as_ref()
+                .and_then(|spec| spec.availability.as_ref())
+                .map(|availability| availability.to_string())
+                .unwrap_or_default(),
+        })
+        .collect();
+
+    result.sort_by(|a, b| a.name.cmp(&b.name));
+    result
+}
+
+fn create_event(container_name: &str, event: EventType) -> Event {
+    Event {
+        container_name: container_name.to_owned(),
+        event,
+    }
+}
+
+#[cfg(test)]
+mod must {
+    use bollard::models::{
+        Node, NodeDescription, NodeSpec, NodeSpecAvailabilityEnum, NodeState, NodeStatus, Service,
+        ServiceSpec, ServiceSpecMode, ServiceSpecModeReplicated, Task, TaskState, TaskStatus,
+    };
+
+    use crate::events::{ContainerEvent, EventType, SwarmNode, TaskTransition, HOST};
+
+    use super::SwarmState;
+
+    fn create_service(replicas: i64) -> Service {
+        Service {
+            id: Some("s1".to_owned()),
+            spec: Some(ServiceSpec {
+                name: Some("web".to_owned()),
+                mode: Some(ServiceSpecMode {
+                    replicated: Some(ServiceSpecModeReplicated {
+                        replicas: Some(replicas),
+                    }),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            }),
+            ..Default::default()
+        }
+    }
+
+    fn create_task(id: &str, slot: i64, state: TaskState) -> Task {
+        Task {
+            id: Some(id.to_owned()),
+            service_id: Some("s1".to_owned()),
+            slot: Some(slot),
+            node_id: Some("n1".to_owned()),
+            desired_state: Some(TaskState::RUNNING),
+            status: Some(TaskStatus {
+                state: Some(state),
+                ..Default::default()
+            }),
+            ..Default::default()
+        }
+    }
+
+    fn create_node(availability: NodeSpecAvailabilityEnum) -> Node {
+        Node {
+            id: Some("n1".to_owned()),
+            spec: Some(NodeSpec {
+                availability: Some(availability),
+                ..Default::default()
+            }),
+            description: Some(NodeDescription {
+                hostname: Some("pi".to_owned()),
+                ..Default::default()
+            }),
+            status: Some(NodeStatus {
+                state: Some(NodeState::READY),
+                ..Default::default()
+            }),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn publish_service_devices_keyed_by_service_name() {
+        // arrange
+        let mut state = SwarmState::default();
+
+        // act
+        let events = state.update(
+            &[create_service(2)],
+            &[create_task("t1", 1, TaskState::RUNNING)],
+            &[],
+        );
+
+        // assert
+        assert!(events
+            .iter()
+            .all(|event| event.container_name == "_service_web"));
+        assert_eq!(
+            vec![
+                EventType::State(ContainerEvent::Create),
+                EventType::DesiredReplicas(2),
+                EventType::RunningReplicas(1),
+                EventType::TaskState(TaskTransition {
+                    task: "web.1".to_owned(),
+                    node: "".to_owned(),
+                    previous_state: None,
+                    state: "running".to_owned(),
+                }),
+            ],
+            events
+                .into_iter()
+                .map(|event| event.event)
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn publish_changes_only() {
+        // arrange
+        let mut state = SwarmState::default();
+        state.update(
+            &[create_service(2)],
+            &[create_task("t1", 1, TaskState::STARTING)],
+            &[],
+        );
+
+        // act
+        let events = state.update(
+            &[create_service(2)],
+            &[create_task("t1", 1, TaskState::RUNNING)],
+            &[],
+        );
+
+        // assert
+        assert_eq!(
+            vec![
+                EventType::RunningReplicas(1),
+                EventType::TaskState(TaskTransition {
+                    task: "web.1".to_owned(),
+                    node: "".to_owned(),
+                    previous_state: Some("starting".to_owned()),
+                    state: "running".to_owned(),
+                }),
+            ],
+            events
+                .into_iter()
+                .map(|event| event.event)
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn remove_services_no_longer_listed() {
+        // arrange
+        let mut state = SwarmState::default();
+        state.update(&[create_service(1)], &[], &[]);
+
+        // act
+        let events = state.update(&[], &[], &[]);
+
+        // assert
+        assert_eq!(1, events.len());
+        assert_eq!("_service_web", events[0].container_name);
+        assert_eq!(EventType::State(ContainerEvent::Destroy), events[0].event);
+    }
+
+    #[test]
+    fn publish_node_availability_on_host() {
+        // arrange
+        let mut state = SwarmState::default();
+        state.update(&[], &[], &[create_node(NodeSpecAvailabilityEnum::ACTIVE)]);
+
+        // act
+        let events = state.update(&[], &[], &[create_node(NodeSpecAvailabilityEnum::DRAIN)]);
+
+        // assert
+        assert_eq!(1, events.len());
+        assert_eq!(HOST, events[0].container_name);
+        assert_eq!(
+            EventType::SwarmNodes(vec![SwarmNode {
+                name: "pi".to_owned(),
+                state: "ready".to_owned(),
+                availability: "drain".to_owned(),
+            }]),
+            events[0].event
+        );
+    }
+}
@@ -0,0 +1,295 @@
+use std::{collections::HashSet, time::Duration};
+
+use bollard::{models::Image, Docker};
+use tokio::{
+    task,
+    time::{self, Instant},
+};
+use tracing::{debug, warn};
+
+use crate::{
+    bus::{Publisher, Subscriber},
+    configuration::Configuration,
+    events::{ContainerEvent, Event, EventType, ImageUpdate},
+    shutdown::{self, Cancellation},
+};
+
+use self::{reference::Reference, registry::Registry};
+
+use super::container;
+
+mod reference;
+mod registry;
+
+pub async fn source(
+    mut subscriber: Subscriber,
+    publisher: Publisher,
+    client: Docker,
+    conf: &Configuration,
+    mut cancellation: Cancellation,
+) {
+    let updates = match &conf.updates {
+        Some(updates) => updates.clone(),
+        None => return,
+    };
+
+    task::spawn(async move {
+        let registry = Registry::new(&updates);
+        let period = Duration::from_secs(updates.interval);
+        let mut interval = time::interval_at(Instant::now() + period, period);
+
+        let mut containers = HashSet::new();
+        loop {
+            tokio::select! {
+                received = subscriber.recv() => match received {
+                    Some(event) => handle_event(event, &mut containers, &client, &registry, &publisher),
+                    None => break,
+                },
+                _ = interval.tick() => {
+                    for container_name in containers.iter() {
+                        start_check(container_name, &client, &registry, &publisher);
+                    }
+                }
+                _ = shutdown::cancelled(&mut cancellation) => break,
+            }
+        }
+    });
+}
+
+fn handle_event(
+    event: Event,
+    containers: &mut HashSet<String>,
+    client: &Docker,
+    registry: &Registry,
+    publisher: &Publisher,
+) {
+    match &event.event {
+        EventType::State(ContainerEvent::Start) => {
+            start_check(&event.container_name, client, registry, publisher);
+            containers.insert(event.container_name);
+        }
+        EventType::State(ContainerEvent::Stop)
+        | EventType::State(ContainerEvent::Die)
+        | EventType::State(ContainerEvent::Destroy) => {
+            containers.remove(&event.container_name);
+        }
+        _ => {}
+    }
+}
+
+fn start_check(container_name: &str, client: &Docker, registry: &Registry, publisher: &Publisher) {
+    let container_name = container_name.to_owned();
+    let client = client.clone();
+    let registry = registry.clone();
+    let publisher = publisher.clone();
+
+    task::spawn(async move {
+        for event in check(&container_name, &client, &registry).await {
+            publisher.send(event);
+        }
+    });
+}
+
+async fn check(container_name: &str, client: &Docker, registry: &Registry) -> Vec<Event> {
+    let container = match container::get_by_name(client, container_name).await {
+        Some(container) => container,
+        None => return vec![],
+    };
+
+    let (image, image_id) = match (container.image, container.image_id) {
+        (Some(image), Some(image_id)) => (image, image_id),
+        _ => return vec![],
+    };
+
+    let reference = reference::parse(&image, registry.endpoint());
+    if reference.digest.is_some() {
+        debug!("skipping update check for pinned image {}", image);
+        return vec![];
+    }
+
+    let inspect = match client.inspect_image(&image_id).await {
+        Ok(inspect) => inspect,
+        Err(e) => {
+            warn!("could not inspect image {}: {}", image, e);
+            return vec![];
+        }
+    };
+
+    let installed_digest = match get_installed_digest(&inspect, &reference, registry.endpoint()) {
+        Some(digest) => digest,
+        None => {
+            debug!("skipping update check for local image {}", image);
+            return vec![];
+        }
+    };
+
+    let latest_digest = match registry.digest(&reference).await {
+        Ok(digest) => digest,
+        Err(e) => {
+            warn!("could not resolve remote digest for {}: {}", image, e);
+            return vec![];
+        }
+    };
+
+    get_update_events(container_name, &inspect, &installed_digest, &latest_digest)
+}
+
+fn get_installed_digest(
+    image: &Image,
+    reference: &Reference,
+    default_registry: &str,
+) -> Option<String> {
+    let repo_digests = image.repo_digests.as_deref().unwrap_or_default();
+
+    let mut digests = repo_digests.iter().filter_map(|repo_digest| {
+        let digest_reference = reference::parse(repo_digest, default_registry);
+        if digest_reference.domain == reference.domain
+            && digest_reference.repository == reference.repository
+        {
+            digest_reference.digest
+        } else {
+            None
+        }
+    });
+
+    digests.next()
+}
+
+fn get_update_events(
+    container_name: &str,
+    image: &Image,
+    installed_digest: &str,
+    latest_digest: &str,
+) -> Vec<Event> {
+    let available = installed_digest != latest_digest;
+    let installed_version = match get_version_label(image) {
+        Some(version) => version,
+        None => shorten(installed_digest),
+    };
+
+    let latest_version = match available {
+        true => shorten(latest_digest),
+        false => installed_version.to_owned(),
+    };
+
+    vec![
+        Event {
+            container_name: container_name.to_owned(),
+            event: EventType::Update(ImageUpdate {
+                installed_version,
+                latest_version,
+            }),
+        },
+        Event {
+            container_name: container_name.to_owned(),
+            event: EventType::UpdateAvailable(available),
+        },
+    ]
+}
+
+fn get_version_label(image: &Image) -> Option<String> {
+    image
+        .config
+        .as_ref()
+        .and_then(|config| config.labels.as_ref())
+        .and_then(|labels| labels.get("org.opencontainers.image.version"))
+        .map(|version| version.to_owned())
+}
+
+fn shorten(digest: &str) -> String {
+    let hash = digest.rsplit(':').next().unwrap_or(digest);
+
+    hash.chars().take(12).collect()
+}
+
+#[cfg(test)]
+mod must {
+    use bollard::models::Image;
+
+    use crate::events::{EventType, ImageUpdate};
+
+    use super::reference;
+
+    const HUB: &str = "https://registry-1.docker.io";
+
+    fn create_image(repo_digests: Vec<&str>) -> Image {
+        Image {
+            repo_digests: Some(repo_digests.into_iter().map(|d| d.to_owned()).collect()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn resolve_installed_digest_of_matching_repository() {
+        // arrange
+        let image = create_image(vec!["ghcr.io/owner/nginx@sha256:aaaa", "nginx@sha256:bbbb"]);
+
+        // act
+        let digest =
+            super::get_installed_digest(&image, &reference::parse("nginx:latest", HUB), HUB);
+
+        // assert
+        assert_eq!(Some("sha256:bbbb".to_owned()), digest);
+    }
+
+    #[test]
+    fn return_none_for_images_without_repo_digests() {
+        // arrange
+        let image = create_image(vec![]);
+
+        // act
+        let digest =
+            super::get_installed_digest(&image, &reference::parse("nginx:latest", HUB), HUB);
+
+        // assert
+        assert_eq!(None, digest);
+    }
+
+    #[test]
+    fn publish_update_if_digests_differ() {
+        // arrange
+        let image = create_image(vec![]);
+
+        // act
+        let events = super::get_update_events(
+            "nginx",
+            &image,
+            "sha256:0123456789abcdef",
+            "sha256:fedcba9876543210",
+        );
+
+        // assert
+        assert_eq!(
+            EventType::Update(ImageUpdate {
+                installed_version: "0123456789ab".to_owned(),
+                latest_version: "fedcba987654".to_owned(),
+            }),
+            events[0].event
+        );
+        assert_eq!(EventType::UpdateAvailable(true), events[1].event);
+    }
+
+    #[test]
+    fn publish_installed_version_as_latest_if_digests_match() {
+        // arrange
+        let image = create_image(vec![]);
+
+        // act
+        let events = super::get_update_events(
+            "nginx",
+            &image,
+            "sha256:0123456789ab",
+            "sha256:0123456789ab",
+        );
+
+        // assert
+        assert_eq!(
+            EventType::Update(ImageUpdate {
+                installed_version: "0123456789ab".to_owned(),
+                latest_version: "0123456789ab".to_owned(),
+            }),
+            events[0].event
+        );
+        assert_eq!(EventType::UpdateAvailable(false), events[1].event);
+    }
+}
@@ -0,0 +1,166 @@
+#[derive(Clone, Debug, PartialEq)]
+pub struct Reference {
+    pub domain: Option<String>,
+    pub repository: String,
+    pub tag: String,
+    pub digest: Option<String>,
+}
+
+const DOCKER_HUB: &str = "registry-1.docker.io";
+
+// images without a domain belong to the given default registry, only docker hub keeps official images
+// in library/
+pub fn parse(image: &str, default_registry: &str) -> Reference {
+    let (name, digest) = match image.split_once('@') {
+        Some((name, digest)) => (name, Some(digest.to_owned())),
+        None => (image, None),
+    };
+
+    let default_authority = default_registry
+        .rsplit("://")
+        .next()
+        .unwrap_or_default()
+        .trim_end_matches('/');
+    let default_hub = is_docker_hub(default_authority);
+
+    let (domain, remainder) = split_domain(name, default_hub);
+    let (repository, tag) = split_tag(remainder);
+
+    let hub = match &domain {
+        Some(domain) => is_docker_hub(domain),
+        None => default_hub,
+    };
+
+    let repository = match hub && !repository.contains('/') {
+        true => format!("library/{}", repository),
+        false => repository.to_owned(),
+    };
+
+    Reference {
+        domain,
+        repository,
+        tag: tag.unwrap_or("latest").to_owned(),
+        digest,
+    }
+}
+
+fn split_domain(name: &str, default_hub: bool) -> (Option<String>, &str) {
+    let (first, remainder) = match name.split_once('/') {
+        Some(split) => split,
+        None => return (None, name),
+    };
+
+    if !first.contains('.') && !first.contains(':') && first != "localhost" {
+        return (None, name);
+    }
+
+    match (is_docker_hub(first), default_hub) {
+        (true, true) => (None, remainder),
+        (true, false) => (Some(DOCKER_HUB.to_owned()), remainder),
+        _ => (Some(first.to_owned()), remainder),
+    }
+}
+
+fn is_docker_hub(authority: &str) -> bool {
+    matches!(
+        authority.to_ascii_lowercase().as_str(),
+        "docker.io" | "index.docker.io" | "registry-1.docker.io"
+    )
+}
+
+fn split_tag(remainder: &str) -> (&str, Option<&str>) {
+    match remainder.rsplit_once(':') {
+        Some((repository, tag)) if !tag.contains('/') => (repository, Some(tag)),
+        _ => (remainder, None),
+    }
+}
+
+#[cfg(test)]
+mod must {
+    use super::Reference;
+
+    const HUB: &str = "https://registry-1.docker.io";
+
+    #[test]
+    fn resolve_official_images_to_library_repository() {
+        // act
+        let reference = super::parse("nginx", HUB);
+
+        // assert
+        assert_eq!(
+            Reference {
+                domain: None,
+                repository: "library/nginx".to_owned(),
+                tag: "latest".to_owned(),
+                digest: None,
+            },
+            reference
+        );
+    }
+
+    #[test]
+    fn resolve_tag_and_user_repository() {
+        // act
+        let reference = super::parse("serowy/docker2mqtt:1.2.3", HUB);
+
+        // assert
+        assert_eq!(None, reference.domain);
+        assert_eq!("serowy/docker2mqtt", reference.repository);
+        assert_eq!("1.2.3", reference.tag);
+    }
+
+    #[test]
+    fn resolve_docker_hub_domain_as_default_registry() {
+        // act
+        let reference = super::parse("docker.io/library/redis:6", HUB);
+
+        // assert
+        assert_eq!(None, reference.domain);
+        assert_eq!("library/redis", reference.repository);
+        assert_eq!("6", reference.tag);
+    }
+
+    #[test]
+    fn resolve_domain_with_port() {
+        // act
+        let reference = super::parse("localhost:5000/tools/borg", HUB);
+
+        // assert
+        assert_eq!(Some("localhost:5000".to_owned()), reference.domain);
+        assert_eq!("tools/borg", reference.repository);
+        assert_eq!("latest", reference.tag);
+    }
+
+    #[test]
+    fn resolve_digest() {
+        // act
+        let reference = super::parse("ghcr.io/owner/image:edge@sha256:1234", HUB);
+
+        // assert
+        assert_eq!(Some("ghcr.io".to_owned()), reference.domain);
+        assert_eq!("owner/image", reference.repository);
+        assert_eq!("edge", reference.tag);
+        assert_eq!(Some("sha256:1234".to_owned()), reference.digest);
+    }
+
+    #[test]
+    fn keep_repository_of_custom_registry() {
+        // act
+        let reference = super::parse("myapp:2", "http://localhost:5000/");
+
+        // assert
+        assert_eq!(None, reference.domain);
+        assert_eq!("myapp", reference.repository);
+        assert_eq!("2", reference.tag);
+    }
+
+    #[test]
+    fn resolve_docker_hub_domain_next_to_custom_registry() {
+        // act
+        let reference = super::parse("docker.io/redis:6", "http://localhost:5000");
+
+        // assert
+        assert_eq!(Some("registry-1.docker.io".to_owned()), reference.domain);
+        assert_eq!("library/redis", reference.repository);
+    }
+}
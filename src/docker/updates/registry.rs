@@ -0,0 +1,571 @@
+use std::{collections::HashMap, fmt};
+
+use hyper::{
+    client::HttpConnector,
+    header::{ACCEPT, AUTHORIZATION, WWW_AUTHENTICATE},
+    http, Body, Client, Method, Request, Response, StatusCode, Uri,
+};
+use hyper_rustls::HttpsConnector;
+use serde::Deserialize;
+
+use crate::configuration::Updates;
+
+use super::reference::Reference;
+
+const MANIFEST_TYPES: &str = "application/vnd.oci.image.index.v1+json, \
+    application/vnd.docker.distribution.manifest.list.v2+json, \
+    application/vnd.oci.image.manifest.v1+json, \
+    application/vnd.docker.distribution.manifest.v2+json";
+
+pub type RegistryResult<T> = Result<T, RegistryErr>;
+
+#[derive(Debug)]
+pub enum RegistryErr {
+    Http(hyper::Error),
+    InvalidRequest(http::Error),
+    InvalidToken(serde_json::Error),
+    MissingDigest,
+    Status(StatusCode),
+    UnsupportedChallenge(String),
+}
+
+impl fmt::Display for RegistryErr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RegistryErr::Http(e) => write!(f, "request failed: {}", e),
+            RegistryErr::InvalidRequest(e) => write!(f, "invalid request: {}", e),
+            RegistryErr::InvalidToken(e) => write!(f, "invalid token response: {}", e),
+            RegistryErr::MissingDigest => write!(f, "response contains no digest"),
+            RegistryErr::Status(status) => write!(f, "unexpected status {}", status),
+            RegistryErr::UnsupportedChallenge(challenge) => {
+                write!(f, "unsupported authentication challenge '{}'", challenge)
+            }
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum Challenge {
+    Basic,
+    Bearer(HashMap<String, String>),
+}
+
+#[derive(Deserialize)]
+struct Token {
+    token: Option<String>,
+    access_token: Option<String>,
+}
+
+#[derive(Clone)]
+pub struct Registry {
+    client: Client<HttpsConnector<HttpConnector>>,
+    credentials: Option<String>,
+    endpoint: String,
+}
+
+impl Registry {
+    pub fn new(conf: &Updates) -> Registry {
+        let credentials = match (&conf.username, &conf.password) {
+            (Some(username), Some(password)) => {
+                Some(base64::encode(format!("{}:{}", username, password)))
+            }
+            _ => None,
+        };
+
+        Registry {
+            client: Client::builder().build(HttpsConnector::with_webpki_roots()),
+            credentials,
+            endpoint: conf.registry.trim_end_matches('/').to_owned(),
+        }
+    }
+
+    pub fn endpoint(&self) -> &str {
+        &self.endpoint
+    }
+
+    pub async fn digest(&self, reference: &Reference) -> RegistryResult<String> {
+        let uri = format!(
+            "{}/v2/{}/manifests/{}",
+            self.get_endpoint(reference),
+            reference.repository,
+            reference.tag
+        );
+
+        let mut response = self.head(&uri, None).await?;
+        if response.status() == StatusCode::UNAUTHORIZED {
+            let authorization = self.authorize(&uri, &response).await?;
+            response = self.head(&uri, Some(authorization)).await?;
+        }
+
+        if !response.status().is_success() {
+            return Err(RegistryErr::Status(response.status()));
+        }
+
+        match response.headers().get("docker-content-digest") {
+            Some(digest) => match digest.to_str() {
+                Ok(digest) => Ok(digest.to_owned()),
+                Err(_) => Err(RegistryErr::MissingDigest),
+            },
+            None => Err(RegistryErr::MissingDigest),
+        }
+    }
+
+    fn get_endpoint(&self, reference: &Reference) -> String {
+        let authority = self.endpoint.rsplit("://").next().unwrap_or_default();
+
+        match &reference.domain {
+            Some(domain) if domain != authority => format!("https://{}", domain),
+            _ => self.endpoint.to_owned(),
+        }
+    }
+
+    async fn head(
+        &self,
+        uri: &str,
+        authorization: Option<String>,
+    ) -> RegistryResult<Response<Body>> {
+        let mut request = Request::builder()
+            .method(Method::HEAD)
+            .uri(uri)
+            .header(ACCEPT, MANIFEST_TYPES);
+
+        if let Some(authorization) = authorization {
+            request = request.header(AUTHORIZATION, authorization);
+        }
+
+        self.send(request.body(Body::empty())).await
+    }
+
+    async fn authorize(&self, uri: &str, response: &Response<Body>) -> RegistryResult<String> {
+        let header = response
+            .headers()
+            .get(WWW_AUTHENTICATE)
+            .and_then(|value| value.to_str().ok())
+            .unwrap_or_default();
+
+        match parse_challenge(header) {
+            Some(Challenge::Basic) => match self.get_credentials(uri) {
+                Some(credentials) => Ok(format!("Basic {}", credentials)),
+                None => Err(RegistryErr::Status(StatusCode::UNAUTHORIZED)),
+            },
+            Some(Challenge::Bearer(parameters)) => {
+                let token = self.token(uri, &parameters).await?;
+                Ok(format!("Bearer {}", token))
+            }
+            None => Err(RegistryErr::UnsupportedChallenge(header.to_owned())),
+        }
+    }
+
+    async fn token(
+        &self,
+        uri: &str,
+        parameters: &HashMap<String, String>,
+    ) -> RegistryResult<String> {
+        let realm = match parameters.get("realm") {
+            Some(realm) => realm,
+            None => {
+                return Err(RegistryErr::UnsupportedChallenge(
+                    "missing realm".to_owned(),
+                ))
+            }
+        };
+
+        let query: Vec<String> = vec!["service", "scope"]
+            .into_iter()
+            .filter_map(|key| {
+                parameters
+                    .get(key)
+                    .map(|value| format!("{}={}", key, value))
+            })
+            .collect();
+
+        let mut request =
+            Request::builder()
+                .method(Method::GET)
+                .uri(format!("{}?{}", realm, query.join("&")));
+
+        if let Some(credentials) = self.get_token_credentials(uri, realm) {
+            request = request.header(AUTHORIZATION, format!("Basic {}", credentials));
+        }
+
+        let response = self.send(request.body(Body::empty())).await?;
+        if !response.status().is_success() {
+            return Err(RegistryErr::Status(response.status()));
+        }
+
+        let body = hyper::body::to_bytes(response.into_body())
+            .await
+            .map_err(RegistryErr::Http)?;
+
+        let token: Token = serde_json::from_slice(&body).map_err(RegistryErr::InvalidToken)?;
+        match token.token.or(token.access_token) {
+            Some(token) => Ok(token),
+            None => Err(RegistryErr::Status(StatusCode::UNAUTHORIZED)),
+        }
+    }
+
+    // credentials belong to the configured registry, images of other registries are checked anonymously
+    fn get_credentials(&self, uri: &str) -> Option<&String> {
+        match get_authority(uri) {
+            Some(authority) if Some(&authority) == get_authority(&self.endpoint).as_ref() => {
+                self.credentials.as_ref()
+            }
+            _ => None,
+        }
+    }
+
+    // the configured registry names its token server, e.g. auth.docker.io for docker hub, which gets
+    // the credentials over https only
+    fn get_token_credentials(&self, uri: &str, realm: &str) -> Option<&String> {
+        let credentials = self.get_credentials(uri)?;
+
+        match realm.parse::<Uri>().ok()?.scheme_str() {
+            Some("https") => Some(credentials),
+            _ => None,
+        }
+    }
+
+    async fn send(
+        &self,
+        request: Result<Request<Body>, http::Error>,
+    ) -> RegistryResult<Response<Body>> {
+        let request = request.map_err(RegistryErr::InvalidRequest)?;
+
+        self.client
+            .request(request)
+            .await
+            .map_err(RegistryErr::Http)
+    }
+}
+
+fn get_authority(uri: &str) -> Option<String> {
+    let uri = uri.parse::<Uri>().ok()?;
+    uri.authority()
+        .map(|authority| authority.as_str().to_ascii_lowercase())
+}
+
+fn parse_challenge(header: &str) -> Option<Challenge> {
+    let (scheme, parameters) = match header.trim().split_once(' ') {
+        Some(split) => split,
+        None => (header.trim(), ""),
+    };
+
+    if scheme.eq_ignore_ascii_case("basic") {
+        return Some(Challenge::Basic);
+    }
+
+    if !scheme.eq_ignore_ascii_case("bearer") {
+        return None;
+    }
+
+    let mut result = HashMap::new();
+    let mut remainder = parameters.trim();
+    while let Some((key, value)) = remainder.split_once('=') {
+        let key = key.trim().trim_start_matches(',').trim().to_owned();
+        let value = value.trim_start();
+
+        let (value, rest) = match value.strip_prefix('"') {
+            Some(quoted) => match quoted.split_once('"') {
+                Some((value, rest)) => (value, rest),
+                None => (quoted, ""),
+            },
+            None => match value.split_once(',') {
+                Some((value, rest)) => (value, rest),
+                None => (value, ""),
+            },
+        };
+
+        result.insert(key, value.to_owned());
+        remainder = rest;
+    }
+
+    Some(Challenge::Bearer(result))
+}
+
+#[cfg(test)]
+mod must {
+    use std::{
+        collections::HashMap,
+        convert::Infallible,
+        net::SocketAddr,
+        sync::{Arc, Mutex},
+    };
+
+    use hyper::{
+        header::{AUTHORIZATION, WWW_AUTHENTICATE},
+        service::{make_service_fn, service_fn},
+        Body, Request, Response, Server, StatusCode,
+    };
+
+    use crate::{configuration::Updates, docker::updates::reference};
+
+    use super::{Challenge, Registry, RegistryErr};
+
+    #[test]
+    fn parse_bearer_challenge_parameters() {
+        // arrange
+        let header = r#"Bearer realm="https://auth.docker.io/token",service="registry.docker.io",scope="repository:library/nginx:pull""#;
+
+        // act
+        let challenge = super::parse_challenge(header);
+
+        // assert
+        let mut expected = HashMap::new();
+        expected.insert(
+            "realm".to_owned(),
+            "https://auth.docker.io/token".to_owned(),
+        );
+        expected.insert("service".to_owned(), "registry.docker.io".to_owned());
+        expected.insert(
+            "scope".to_owned(),
+            "repository:library/nginx:pull".to_owned(),
+        );
+
+        assert_eq!(Some(Challenge::Bearer(expected)), challenge);
+    }
+
+    #[test]
+    fn parse_basic_challenge() {
+        // act
+        let challenge = super::parse_challenge(r#"Basic realm="registry""#);
+
+        // assert
+        assert_eq!(Some(Challenge::Basic), challenge);
+    }
+
+    #[test]
+    fn return_none_for_unknown_challenge() {
+        // act
+        let challenge = super::parse_challenge("Digest realm=\"registry\"");
+
+        // assert
+        assert_eq!(None, challenge);
+    }
+
+    #[tokio::test]
+    async fn resolve_digest_with_token_authentication() {
+        // arrange
+        let address = start_registry().await;
+        let registry = create_registry(address);
+
+        // act
+        let digest = registry
+            .digest(&reference::parse(
+                &format!("{}/tools/borg:1.0", address),
+                registry.endpoint(),
+            ))
+            .await;
+
+        // assert
+        assert_eq!("sha256:0123456789abcdef", digest.unwrap());
+    }
+
+    #[tokio::test]
+    async fn return_status_for_unknown_manifest() {
+        // arrange
+        let address = start_registry().await;
+        let registry = create_registry(address);
+
+        // act
+        let digest = registry
+            .digest(&reference::parse(
+                &format!("{}/tools/unknown", address),
+                registry.endpoint(),
+            ))
+            .await;
+
+        // assert
+        match digest {
+            Err(RegistryErr::Status(StatusCode::NOT_FOUND)) => {}
+            result => panic!("unexpected result: {:?}", result),
+        }
+    }
+
+    #[test]
+    fn keep_credentials_from_foreign_registry() {
+        // arrange
+        let registry = create_authenticated_registry("https://registry.example.com");
+
+        // act
+        let own = registry.get_credentials("https://registry.example.com/v2/borg/manifests/1.0");
+        let foreign = registry.get_credentials("https://ghcr.io/v2/borg/manifests/1.0");
+        let spoofed = registry.get_credentials("https://registry.example.com.evil.io/v2/");
+
+        // assert
+        assert!(own.is_some());
+        assert_eq!(None, foreign);
+        assert_eq!(None, spoofed);
+    }
+
+    #[test]
+    fn send_credentials_to_token_realm_of_configured_registry() {
+        // arrange
+        let registry = create_authenticated_registry("https://registry-1.docker.io");
+
+        // act
+        let own = registry.get_token_credentials(
+            "https://registry-1.docker.io/v2/tools/borg/manifests/1.0",
+            "https://auth.docker.io/token",
+        );
+        let foreign = registry.get_token_credentials(
+            "https://ghcr.io/v2/tools/borg/manifests/1.0",
+            "https://ghcr.io/token",
+        );
+        let plain = registry.get_token_credentials(
+            "https://registry-1.docker.io/v2/tools/borg/manifests/1.0",
+            "http://auth.docker.io/token",
+        );
+
+        // assert
+        assert!(own.is_some());
+        assert_eq!(None, foreign);
+        assert_eq!(None, plain);
+    }
+
+    #[tokio::test]
+    async fn keep_credentials_from_plain_token_realm() {
+        // arrange
+        let (address, authorizations) = start_recording_server(|request| {
+            let port = request
+                .headers()
+                .get("host")
+                .and_then(|host| host.to_str().ok())
+                .and_then(|host| host.rsplit(':').next())
+                .unwrap_or_default()
+                .to_owned();
+
+            match request.uri().path() {
+                "/token" => Response::new(Body::from(r#"{"token":"secret"}"#)),
+                _ => Response::builder()
+                    .status(StatusCode::UNAUTHORIZED)
+                    .header(
+                        WWW_AUTHENTICATE,
+                        format!(r#"Bearer realm="http://localhost:{}/token""#, port),
+                    )
+                    .body(Body::empty())
+                    .unwrap(),
+            }
+        })
+        .await;
+
+        let registry = create_authenticated_registry(&format!("http://{}", address));
+
+        // act
+        let _ = registry
+            .digest(&reference::parse(
+                &format!("{}/tools/borg:1.0", address),
+                registry.endpoint(),
+            ))
+            .await;
+
+        // assert
+        let authorizations = authorizations.lock().unwrap();
+        assert_eq!(3, authorizations.len());
+        assert_eq!(None, authorizations[1]);
+    }
+
+    fn create_registry(address: SocketAddr) -> Registry {
+        Registry::new(&Updates {
+            interval: 3600,
+            password: None,
+            registry: format!("http://{}/", address),
+            username: None,
+        })
+    }
+
+    fn create_authenticated_registry(endpoint: &str) -> Registry {
+        Registry::new(&Updates {
+            interval: 3600,
+            password: Some("hunter2".to_owned()),
+            registry: endpoint.to_owned(),
+            username: Some("borg".to_owned()),
+        })
+    }
+
+    async fn start_registry() -> SocketAddr {
+        let make_service = make_service_fn(|_| async {
+            Ok::<_, Infallible>(service_fn(|request| async {
+                Ok::<_, Infallible>(handle(request))
+            }))
+        });
+
+        let server = Server::bind(&([127, 0, 0, 1], 0).into()).serve(make_service);
+        let address = server.local_addr();
+
+        tokio::spawn(server);
+
+        address
+    }
+
+    // records the authorization header of every request
+    async fn start_recording_server(
+        respond: fn(&Request<Body>) -> Response<Body>,
+    ) -> (SocketAddr, Arc<Mutex<Vec<Option<String>>>>) {
+        let authorizations = Arc::new(Mutex::new(vec![]));
+        let recorded = authorizations.clone();
+
+        let make_service = make_service_fn(move |_| {
+            let recorded = recorded.clone();
+            async move {
+                Ok::<_, Infallible>(service_fn(move |request: Request<Body>| {
+                    let recorded = recorded.clone();
+                    async move {
+                        let authorization = request
+                            .headers()
+                            .get(AUTHORIZATION)
+                            .and_then(|value| value.to_str().ok())
+                            .map(String::from);
+                        recorded.lock().unwrap().push(authorization);
+
+                        Ok::<_, Infallible>(respond(&request))
+                    }
+                }))
+            }
+        });
+
+        let server = Server::bind(&([127, 0, 0, 1], 0).into()).serve(make_service);
+        let address = server.local_addr();
+
+        tokio::spawn(server);
+
+        (address, authorizations)
+    }
+
+    fn handle(request: Request<Body>) -> Response<Body> {
+        let host = request
+            .headers()
+            .get("host")
+            .and_then(|host| host.to_str().ok())
+            .unwrap_or_default()
+            .to_owned();
+
+        let authorized = request
+            .headers()
+            .get(AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            == Some("Bearer secret");
+
+        match request.uri().path() {
+            "/token" => Response::new(Body::from(r#"{"token":"secret"}"#)),
+            _ if !authorized => Response::builder()
+                .status(StatusCode::UNAUTHORIZED)
+                .header(
+                    WWW_AUTHENTICATE,
+                    format!(
+                        r#"Bearer realm="http://{}/token",service="registry",scope="repository:tools/borg:pull""#,
+                        host
+                    ),
+                )
+                .body(Body::empty())
+                .unwrap(),
+            "/v2/tools/borg/manifests/1.0" => Response::builder()
+                .header("docker-content-digest", "sha256:0123456789abcdef")
+                .body(Body::empty())
+                .unwrap(),
+            _ => Response::builder()
+                .status(StatusCode::NOT_FOUND)
+                .body(Body::empty())
+                .unwrap(),
+        }
+    }
+}
@@ -1,5 +1,63 @@
 use std::fmt;
 
+use serde::{Deserialize, Serialize};
+
+// docker requires container names to start with an alphanumeric character, thus the host device
+// can not collide with any container
+pub const HOST: &str = "_host";
+
+// swarm services are published as devices of their own, prefixed the same way to keep them apart
+// from standalone containers sharing the service name
+pub const SERVICE_PREFIX: &str = "_service_";
+
+// names of all event types, kept in the order of the variants, e.g. to split discovery unique ids
+pub const EVENT_NAMES: [&str; 44] = [
+    "build_cache_size",
+    "container_id",
+    "containers_paused",
+    "containers_running",
+    "containers_stopped",
+    "cpu_statistics",
+    "cpu_usage",
+    "cpus",
+    "dangling_images",
+    "desired_replicas",
+    "downtime",
+    "engine_version",
+    "exit_code",
+    "image",
+    "images",
+    "images_size",
+    "kernel_version",
+    "last_log_alert",
+    "logs",
+    "log_alert",
+    "logs_stderr",
+    "logs_stdout",
+    "logs_suppressed",
+    "memory_statistics",
+    "memory_usage",
+    "networks",
+    "operating_system",
+    "orphaned",
+    "reachable",
+    "running_replicas",
+    "state",
+    "swarm_nodes",
+    "task_state",
+    "time_since_start",
+    "total_memory",
+    "update",
+    "unused_networks",
+    "unused_volumes",
+    "update_available",
+    "uptime_24h",
+    "uptime_30d",
+    "uptime_7d",
+    "volumes",
+    "volumes_size",
+];
+
 #[derive(Clone, Debug, PartialEq)]
 pub struct Event {
     pub container_name: String,
@@ -8,28 +66,107 @@ pub struct Event {
 
 #[derive(Clone, Debug, PartialEq)]
 pub enum EventType {
+    BuildCacheSize(u64),
+    ContainerId(String),
+    ContainersPaused(u64),
+    ContainersRunning(u64),
+    ContainersStopped(u64),
+    CpuStatistics(Vec<Statistics>),
     CpuUsage(f64),
+    Cpus(u64),
+    DanglingImages(u64),
+    DesiredReplicas(u64),
+    Downtime(u64),
+    EngineVersion(String),
+    ExitCode(i64),
     Image(String),
-    Log(String),
+    Images(u64),
+    ImagesSize(u64),
+    KernelVersion(String),
+    LastLogAlert(Option<Alert>),
+    Log(LogLine),
+    LogAlert(Alert),
+    LogStderr(LogLine),
+    LogStdout(LogLine),
+    LogsSuppressed(u64),
+    MemoryStatistics(Vec<Statistics>),
     MemoryUsage(f64),
+    Networks(Vec<String>),
+    OperatingSystem(String),
+    Orphaned(Vec<String>),
+    Reachable(bool),
+    RunningReplicas(u64),
     State(ContainerEvent),
+    SwarmNodes(Vec<SwarmNode>),
+    TaskState(TaskTransition),
+    TimeSinceStart(u64),
+    TotalMemory(u64),
+    Update(ImageUpdate),
+    UnusedNetworks(Vec<String>),
+    UnusedVolumes(Vec<String>),
+    UpdateAvailable(bool),
+    Uptime24h(f64),
+    Uptime30d(f64),
+    Uptime7d(f64),
+    Volumes(Vec<String>),
+    VolumesSize(u64),
 }
 
 impl fmt::Display for EventType {
     fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
         let value = match self {
+            EventType::BuildCacheSize(_) => "build_cache_size",
+            EventType::ContainerId(_) => "container_id",
+            EventType::ContainersPaused(_) => "containers_paused",
+            EventType::ContainersRunning(_) => "containers_running",
+            EventType::ContainersStopped(_) => "containers_stopped",
+            EventType::CpuStatistics(_) => "cpu_statistics",
             EventType::CpuUsage(_) => "cpu_usage",
+            EventType::Cpus(_) => "cpus",
+            EventType::DanglingImages(_) => "dangling_images",
+            EventType::DesiredReplicas(_) => "desired_replicas",
+            EventType::Downtime(_) => "downtime",
+            EventType::EngineVersion(_) => "engine_version",
+            EventType::ExitCode(_) => "exit_code",
             EventType::Image(_) => "image",
+            EventType::Images(_) => "images",
+            EventType::ImagesSize(_) => "images_size",
+            EventType::KernelVersion(_) => "kernel_version",
+            EventType::LastLogAlert(_) => "last_log_alert",
             EventType::Log(_) => "logs",
+            EventType::LogAlert(_) => "log_alert",
+            EventType::LogStderr(_) => "logs_stderr",
+            EventType::LogStdout(_) => "logs_stdout",
+            EventType::LogsSuppressed(_) => "logs_suppressed",
+            EventType::MemoryStatistics(_) => "memory_statistics",
             EventType::MemoryUsage(_) => "memory_usage",
+            EventType::Networks(_) => "networks",
+            EventType::OperatingSystem(_) => "operating_system",
+            EventType::Orphaned(_) => "orphaned",
+            EventType::Reachable(_) => "reachable",
+            EventType::RunningReplicas(_) => "running_replicas",
             EventType::State(_) => "state",
+            EventType::SwarmNodes(_) => "swarm_nodes",
+            EventType::TaskState(_) => "task_state",
+            EventType::TimeSinceStart(_) => "time_since_start",
+            EventType::TotalMemory(_) => "total_memory",
+            EventType::Update(_) => "update",
+            EventType::UnusedNetworks(_) => "unused_networks",
+            EventType::UnusedVolumes(_) => "unused_volumes",
+            EventType::UpdateAvailable(_) => "update_available",
+            EventType::Uptime24h(_) => "uptime_24h",
+            EventType::Uptime30d(_) => "uptime_30d",
+            EventType::Uptime7d(_) => "uptime_7d",
+            EventType::Volumes(_) => "volumes",
+            EventType::VolumesSize(_) => "volumes_size",
         };
 
         write!(formatter, "{}", value)
     }
 }
 
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
 pub enum ContainerEvent {
     Undefined,
 
@@ -45,3 +182,82 @@ pub enum ContainerEvent {
     Unpause,
     Prune,
 }
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Alert {
+    pub name: String,
+    pub severity: String,
+    pub message: String,
+    pub timestamp: Option<String>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ImageUpdate {
+    pub installed_version: String,
+    pub latest_version: String,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct LogLine {
+    pub message: String,
+    pub stream: LogStream,
+    pub timestamp: Option<String>,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum LogStream {
+    Console,
+    StdErr,
+    StdIn,
+    #[default]
+    StdOut,
+}
+
+impl fmt::Display for LogStream {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        let value = match self {
+            LogStream::Console => "console",
+            LogStream::StdErr => "stderr",
+            LogStream::StdIn => "stdin",
+            LogStream::StdOut => "stdout",
+        };
+
+        write!(formatter, "{}", value)
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Statistics {
+    pub window: u64,
+    pub min: f64,
+    pub avg: f64,
+    pub max: f64,
+    pub p95: f64,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SwarmNode {
+    pub name: String,
+    pub state: String,
+    pub availability: String,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TaskTransition {
+    pub task: String,
+    pub node: String,
+    pub previous_state: Option<String>,
+    pub state: String,
+}
+
+pub fn service_name(service: &str) -> String {
+    format!("{}{}", SERVICE_PREFIX, service)
+}
+
+pub fn is_service(container_name: &str) -> bool {
+    container_name.starts_with(SERVICE_PREFIX)
+}
+
+pub fn is_container(container_name: &str) -> bool {
+    container_name != HOST && !is_service(container_name)
+}
@@ -0,0 +1,214 @@
+use std::{
+    collections::BTreeMap,
+    net::SocketAddr,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use hyper::{Client, StatusCode, Uri};
+use lazy_static::lazy_static;
+use serde_json::json;
+use tokio::time;
+
+use crate::configuration::Configuration;
+
+const HEALTHCHECK_TIMEOUT: Duration = Duration::from_secs(5);
+
+lazy_static! {
+    static ref HEALTH: Mutex<Health> = Mutex::new(Health::default());
+}
+
+#[derive(Default)]
+struct Health {
+    docker_reachable: bool,
+    last_docker_event: Option<Instant>,
+    mqtt_connected: bool,
+    tasks: BTreeMap<&'static str, bool>,
+}
+
+impl Health {
+    fn is_alive(&self) -> bool {
+        self.tasks.values().all(|alive| *alive)
+    }
+
+    fn is_ready(&self) -> bool {
+        self.is_alive() && self.docker_reachable && self.mqtt_connected
+    }
+
+    fn to_json(&self, now: Instant) -> String {
+        let last_event_age = self
+            .last_docker_event
+            .map(|received| now.saturating_duration_since(received).as_secs());
+
+        json!({
+            "docker": {
+                "reachable": self.docker_reachable,
+                "last_event_age_seconds": last_event_age,
+            },
+            "mqtt": {
+                "connected": self.mqtt_connected,
+            },
+            "tasks": self.tasks,
+        })
+        .to_string()
+    }
+}
+
+pub struct TaskGuard {
+    name: &'static str,
+}
+
+impl Drop for TaskGuard {
+    fn drop(&mut self) {
+        HEALTH.lock().unwrap().tasks.insert(self.name, false);
+    }
+}
+
+pub fn alive(name: &'static str) -> TaskGuard {
+    HEALTH.lock().unwrap().tasks.insert(name, true);
+
+    TaskGuard { name }
+}
+
+pub fn set_docker_reachable(reachable: bool) {
+    HEALTH.lock().unwrap().docker_reachable = reachable;
+}
+
+pub fn docker_event_received() {
+    HEALTH.lock().unwrap().last_docker_event = Some(Instant::now());
+}
+
+pub fn set_mqtt_connected(connected: bool) {
+    HEALTH.lock().unwrap().mqtt_connected = connected;
+}
+
+pub fn liveness() -> (bool, String) {
+    let health = HEALTH.lock().unwrap();
+
+    (health.is_alive(), health.to_json(Instant::now()))
+}
+
+pub fn readiness() -> (bool, String) {
+    let health = HEALTH.lock().unwrap();
+
+    (health.is_ready(), health.to_json(Instant::now()))
+}
+
+pub async fn check(conf: &Configuration) -> i32 {
+    let http = match &conf.http {
+        Some(http) => http,
+        None => {
+            println!("http server is disabled, health can not be checked");
+            return 0;
+        }
+    };
+
+    let mut address: SocketAddr = match http.address.parse() {
+        Ok(address) => address,
+        Err(e) => {
+            println!("invalid http address {}: {}", http.address, e);
+            return 1;
+        }
+    };
+
+    if address.ip().is_unspecified() {
+        address.set_ip([127, 0, 0, 1].into());
+    }
+
+    let uri: Uri = match format!("http://{}/readyz", address).parse() {
+        Ok(uri) => uri,
+        Err(e) => {
+            println!("invalid health uri: {}", e);
+            return 1;
+        }
+    };
+
+    match time::timeout(HEALTHCHECK_TIMEOUT, Client::new().get(uri)).await {
+        Ok(Ok(response)) if response.status() == StatusCode::OK => 0,
+        Ok(Ok(response)) => {
+            println!("not ready: {}", response.status());
+            1
+        }
+        Ok(Err(e)) => {
+            println!("health request failed: {}", e);
+            1
+        }
+        Err(_) => {
+            println!("health request timed out");
+            1
+        }
+    }
+}
+
+#[cfg(test)]
+mod must {
+    use std::time::{Duration, Instant};
+
+    use super::Health;
+
+    fn create_health() -> Health {
+        let mut health = Health {
+            docker_reachable: true,
+            mqtt_connected: true,
+            ..Default::default()
+        };
+        health.tasks.insert("docker_events", true);
+
+        health
+    }
+
+    #[test]
+    fn be_ready_if_docker_and_mqtt_are_connected() {
+        // arrange
+        let health = create_health();
+
+        // act
+        let ready = health.is_ready();
+
+        // assert
+        assert!(ready);
+    }
+
+    #[test]
+    fn not_be_ready_while_mqtt_is_disconnected() {
+        // arrange
+        let mut health = create_health();
+        health.mqtt_connected = false;
+
+        // act
+        let ready = health.is_ready();
+
+        // assert
+        assert!(health.is_alive());
+        assert!(!ready);
+    }
+
+    #[test]
+    fn not_be_alive_if_a_task_stopped() {
+        // arrange
+        let mut health = create_health();
+        health.tasks.insert("mqtt_publish", false);
+
+        // act
+        let alive = health.is_alive();
+
+        // assert
+        assert!(!alive);
+        assert!(!health.is_ready());
+    }
+
+    #[test]
+    fn report_age_of_last_docker_event() {
+        // arrange
+        let now = Instant::now();
+        let mut health = create_health();
+        health.last_docker_event = Some(now - Duration::from_secs(42));
+
+        // act
+        let json = health.to_json(now);
+
+        // assert
+        assert!(json.contains("\"last_event_age_seconds\":42"));
+        assert!(json.contains("\"docker_events\":true"));
+    }
+}
@@ -0,0 +1,124 @@
+use std::{convert::Infallible, net::SocketAddr};
+
+use hyper::{
+    header::CONTENT_TYPE,
+    service::{make_service_fn, service_fn},
+    Body, Method, Request, Response, Server, StatusCode,
+};
+use tokio::task;
+use tracing::{error, info};
+
+use crate::{configuration::Configuration, health, metrics};
+
+const HEALTH_CONTENT_TYPE: &str = "application/json";
+const METRICS_CONTENT_TYPE: &str = "text/plain; version=0.0.4";
+
+pub async fn task(conf: &Configuration) {
+    let http = match &conf.http {
+        Some(http) => http.clone(),
+        None => return,
+    };
+
+    let address: SocketAddr = match http.address.parse() {
+        Ok(address) => address,
+        Err(e) => {
+            error!("invalid http address {}: {}", http.address, e);
+            return;
+        }
+    };
+
+    let make_service = make_service_fn(|_| async {
+        Ok::<_, Infallible>(service_fn(|request| async move {
+            Ok::<_, Infallible>(route(&request))
+        }))
+    });
+
+    let server = match Server::try_bind(&address) {
+        Ok(builder) => builder.serve(make_service),
+        Err(e) => {
+            error!("could not bind http server to {}: {}", address, e);
+            return;
+        }
+    };
+
+    info!("serving http on {}", address);
+    task::spawn(async move {
+        if let Err(e) = server.await {
+            error!("http server failed: {}", e);
+        }
+    });
+}
+
+fn route(request: &Request<Body>) -> Response<Body> {
+    match (request.method(), request.uri().path()) {
+        (&Method::GET, "/metrics") => Response::builder()
+            .header(CONTENT_TYPE, METRICS_CONTENT_TYPE)
+            .body(Body::from(metrics::render()))
+            .unwrap(),
+        (&Method::GET, "/healthz") => get_health_response(health::liveness()),
+        (&Method::GET, "/readyz") => get_health_response(health::readiness()),
+        _ => Response::builder()
+            .status(StatusCode::NOT_FOUND)
+            .body(Body::empty())
+            .unwrap(),
+    }
+}
+
+fn get_health_response((healthy, body): (bool, String)) -> Response<Body> {
+    let status = match healthy {
+        true => StatusCode::OK,
+        false => StatusCode::SERVICE_UNAVAILABLE,
+    };
+
+    Response::builder()
+        .status(status)
+        .header(CONTENT_TYPE, HEALTH_CONTENT_TYPE)
+        .body(Body::from(body))
+        .unwrap()
+}
+
+#[cfg(test)]
+mod must {
+    use hyper::{Body, Method, Request, StatusCode};
+
+    #[test]
+    fn serve_metrics() {
+        // arrange
+        let request = Request::builder()
+            .method(Method::GET)
+            .uri("/metrics")
+            .body(Body::empty())
+            .unwrap();
+
+        // act
+        let response = super::route(&request);
+
+        // assert
+        assert_eq!(StatusCode::OK, response.status());
+    }
+
+    #[test]
+    fn return_unavailable_if_unhealthy() {
+        // act
+        let response = super::get_health_response((false, "{}".to_owned()));
+
+        // assert
+        assert_eq!(StatusCode::SERVICE_UNAVAILABLE, response.status());
+    }
+
+    #[test]
+    fn return_not_found_for_unknown_paths() {
+        // arrange
+        let request = Request::builder()
+            .method(Method::GET)
+            .uri("/unknown")
+            .body(Body::empty())
+            .unwrap();
+
+        // act
+        let response = super::route(&request);
+
+        // assert
+        assert_eq!(StatusCode::NOT_FOUND, response.status());
+    }
+}
@@ -1,3 +1,5 @@
+use std::path::Path;
+
 use tracing_appender::{
     non_blocking::WorkerGuard,
     rolling::{RollingFileAppender, Rotation},
@@ -10,7 +12,7 @@ use tracing_subscriber::{
 
 use crate::configuration::Configuration;
 
-pub fn init(conf: &Configuration) -> Vec<WorkerGuard> {
+pub fn init(conf: &Configuration, log_dir: &Path) -> Vec<WorkerGuard> {
     LogTracer::init().expect("Unable to setup log tracer!");
 
     let mut guards = vec![];
@@ -21,7 +23,7 @@ pub fn init(conf: &Configuration) -> Vec<WorkerGuard> {
 
     let app_name = concat!(env!("CARGO_PKG_NAME"), "-", env!("CARGO_PKG_VERSION")).to_string();
 
-    let file_appender = RollingFileAppender::new(Rotation::DAILY, "/docker2mqtt/logs", "d2m");
+    let file_appender = RollingFileAppender::new(Rotation::DAILY, log_dir, "d2m");
     let (file_writer, file_guard) = tracing_appender::non_blocking(file_appender);
     let file_logging_layer = BunyanFormattingLayer::new(app_name, file_writer);
     guards.push(file_guard);
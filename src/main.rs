@@ -1,29 +1,112 @@
-use tokio::sync::{broadcast, oneshot};
+use structopt::StructOpt;
+use tokio::{sync::oneshot, task};
 
-use crate::configuration::Configuration;
+use crate::{
+    cli::{Args, Command},
+    configuration::Configuration,
+};
 
+mod bus;
+mod cli;
 mod configuration;
 mod docker;
 mod events;
+mod health;
+mod http;
 mod logging;
+mod metrics;
 mod mqtt;
 mod persistence;
+mod reload;
+mod shutdown;
+mod uptime;
 
 #[tokio::main]
 async fn main() {
-    let conf = Configuration::new();
-    let _guards = logging::init(&conf);
+    let args = Args::from_args();
+    match &args.command {
+        Some(Command::Migrate { from, to }) => {
+            match persistence::migrate(*from, *to, &args.data_dir) {
+                Ok(count) => println!("migrated {} records from {} to {}", count, from, to),
+                Err(e) => {
+                    eprintln!("{}", e);
+                    std::process::exit(1);
+                }
+            }
+            return;
+        }
+        Some(Command::PrintDefaultConfig) => {
+            print!("{}", Configuration::default_yaml());
+            return;
+        }
+        _ => {}
+    }
+
+    let conf = match Configuration::load(args.config.as_deref()) {
+        Ok(conf) => conf,
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+    };
+
+    match args.command {
+        Some(Command::CheckConfig) => {
+            println!("configuration is valid");
+            return;
+        }
+        Some(Command::Healthcheck) => std::process::exit(health::check(&conf).await),
+        Some(Command::PrintConfig) => {
+            print!("{}", conf.redacted_yaml());
+            return;
+        }
+        _ => {}
+    }
+
+    let guards = logging::init(&conf, &args.log_dir);
 
     let (repo_init_sender, repo_init_receiver) = oneshot::channel();
-    let (mqtt_sender, mqtt_receiver) = broadcast::channel(100);
-    let repo_receiver = mqtt_sender.subscribe();
+    let publisher = bus::new();
+    let mqtt_subscriber = publisher.subscribe();
+    let repo_subscriber = publisher.subscribe();
+    let history_subscriber = publisher.subscribe_changes();
+    let uptime_subscriber = publisher.subscribe_states();
+    let uptime_publisher = publisher.clone();
+    let shutdown_publisher = publisher.clone();
+    let metrics_subscriber = publisher.subscribe();
+
+    let repo = match persistence::create_repository(&conf, &args.data_dir) {
+        Ok(repo) => repo,
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+    };
+    let (reload_sender, reloads) = reload::channel(&conf);
+    let (cancel, cancellation) = shutdown::channel();
+
+    persistence::init_task(repo_init_sender, &repo).await;
+    docker::task(
+        publisher,
+        repo_init_receiver,
+        &conf,
+        reloads.clone(),
+        cancellation,
+    )
+    .await;
+    persistence::state_task(repo_subscriber, repo.clone(), reloads.clone()).await;
+    persistence::history_task(history_subscriber, repo.clone(), &conf).await;
+    uptime::task(uptime_subscriber, uptime_publisher, repo.clone(), &conf).await;
+    metrics::task(metrics_subscriber, &conf).await;
+    http::task(&conf).await;
+    reload::task(reload_sender, args.config).await;
 
-    let repo = persistence::create_repository(&conf);
+    let mqtt = task::spawn(mqtt::task(mqtt_subscriber, reloads.clone(), repo.clone()));
 
-    persistence::init_task(repo_init_sender, &*repo).await;
-    docker::task(mqtt_sender, repo_init_receiver, &conf).await;
-    persistence::state_task(repo_receiver, repo).await;
+    shutdown::wait().await;
+    let timeout = reloads.borrow().shutdown.timeout;
+    shutdown::run(cancel, shutdown_publisher, mqtt, &repo, timeout).await;
 
-    // must be the last task to start event loop
-    mqtt::task(mqtt_receiver, &conf).await;
+    // writes the buffered log lines
+    drop(guards);
 }
@@ -0,0 +1,124 @@
+use std::{
+    fmt::Write,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Mutex,
+    },
+};
+
+use lazy_static::lazy_static;
+use tokio::task;
+
+use crate::{
+    bus::{Dropped, Subscriber},
+    configuration::Configuration,
+};
+
+use self::registry::Registry;
+
+mod registry;
+
+static EVENTS_PROCESSED: AtomicU64 = AtomicU64::new(0);
+static BUS_COALESCED: AtomicU64 = AtomicU64::new(0);
+static BUS_LOG_OVERFLOW: AtomicU64 = AtomicU64::new(0);
+static MQTT_PUBLISH_FAILURES: AtomicU64 = AtomicU64::new(0);
+static STATS_STREAMS: AtomicU64 = AtomicU64::new(0);
+static LOG_STREAMS: AtomicU64 = AtomicU64::new(0);
+
+lazy_static! {
+    static ref REGISTRY: Mutex<Registry> = Mutex::new(Registry::default());
+}
+
+pub async fn task(mut subscriber: Subscriber, conf: &Configuration) {
+    if conf.http.is_none() {
+        return;
+    }
+
+    task::spawn(async move {
+        while let Some(event) = subscriber.recv().await {
+            REGISTRY.lock().unwrap().update(&event);
+        }
+    });
+}
+
+// counted on send, as subscribers receive coalesced metric events only
+pub fn event_published() {
+    EVENTS_PROCESSED.fetch_add(1, Ordering::Relaxed);
+}
+
+pub fn bus_dropped(dropped: Dropped) {
+    let counter = match dropped {
+        Dropped::Coalesced => &BUS_COALESCED,
+        Dropped::LogOverflow => &BUS_LOG_OVERFLOW,
+    };
+
+    counter.fetch_add(1, Ordering::Relaxed);
+}
+
+pub fn publish_failed() {
+    MQTT_PUBLISH_FAILURES.fetch_add(1, Ordering::Relaxed);
+}
+
+pub fn set_stats_streams(count: usize) {
+    STATS_STREAMS.store(count as u64, Ordering::Relaxed);
+}
+
+pub fn set_log_streams(count: usize) {
+    LOG_STREAMS.store(count as u64, Ordering::Relaxed);
+}
+
+pub fn render() -> String {
+    let mut result = REGISTRY.lock().unwrap().render();
+
+    write_metric(
+        &mut result,
+        "docker2mqtt_events_processed_total",
+        "counter",
+        "Events published on the event bus.",
+        &EVENTS_PROCESSED,
+    );
+    let _ = writeln!(
+        result,
+        "# HELP docker2mqtt_bus_dropped_total Events dropped by the event bus, labeled by reason."
+    );
+    let _ = writeln!(result, "# TYPE docker2mqtt_bus_dropped_total counter");
+    let _ = writeln!(
+        result,
+        "docker2mqtt_bus_dropped_total{{reason=\"coalesced\"}} {}",
+        BUS_COALESCED.load(Ordering::Relaxed)
+    );
+    let _ = writeln!(
+        result,
+        "docker2mqtt_bus_dropped_total{{reason=\"log_overflow\"}} {}",
+        BUS_LOG_OVERFLOW.load(Ordering::Relaxed)
+    );
+    write_metric(
+        &mut result,
+        "docker2mqtt_mqtt_publish_failures_total",
+        "counter",
+        "Messages that could not be published to the mqtt broker.",
+        &MQTT_PUBLISH_FAILURES,
+    );
+    write_metric(
+        &mut result,
+        "docker2mqtt_stats_streams",
+        "gauge",
+        "Active container stats streams.",
+        &STATS_STREAMS,
+    );
+    write_metric(
+        &mut result,
+        "docker2mqtt_log_streams",
+        "gauge",
+        "Active container log streams.",
+        &LOG_STREAMS,
+    );
+
+    result
+}
+
+fn write_metric(result: &mut String, name: &str, kind: &str, help: &str, value: &AtomicU64) {
+    let _ = writeln!(result, "# HELP {} {}", name, help);
+    let _ = writeln!(result, "# TYPE {} {}", name, kind);
+    let _ = writeln!(result, "{} {}", name, value.load(Ordering::Relaxed));
+}
@@ -0,0 +1,215 @@
+use std::{collections::BTreeMap, fmt::Write};
+
+use crate::events::{self, ContainerEvent, Event, EventType};
+
+#[derive(Default)]
+struct Container {
+    cpu_usage: Option<f64>,
+    memory_usage: Option<f64>,
+    state: Option<&'static str>,
+}
+
+#[derive(Default)]
+pub struct Registry {
+    containers: BTreeMap<String, Container>,
+}
+
+impl Registry {
+    pub fn update(&mut self, event: &Event) {
+        if !events::is_container(&event.container_name) {
+            return;
+        }
+
+        match &event.event {
+            EventType::State(ContainerEvent::Destroy) | EventType::State(ContainerEvent::Prune) => {
+                self.containers.remove(&event.container_name);
+            }
+            EventType::State(container_event) => {
+                let container = self.get_container(event);
+                if let Some(state) = get_state(container_event) {
+                    container.state = Some(state);
+                }
+
+                // stopped containers use nothing, their last usage would stay on the gauges
+                if let ContainerEvent::Die | ContainerEvent::Stop = container_event {
+                    container.cpu_usage = None;
+                    container.memory_usage = None;
+                }
+            }
+            EventType::CpuUsage(usage) => self.get_container(event).cpu_usage = Some(*usage),
+            EventType::MemoryUsage(usage) => self.get_container(event).memory_usage = Some(*usage),
+            _ => {}
+        }
+    }
+
+    pub fn render(&self) -> String {
+        let mut result = String::new();
+
+        write_header(
+            &mut result,
+            "docker2mqtt_container_cpu_usage_percent",
+            "Container cpu usage in percent.",
+        );
+        for (name, container) in self.containers.iter() {
+            if let Some(usage) = container.cpu_usage {
+                let _ = writeln!(
+                    result,
+                    "docker2mqtt_container_cpu_usage_percent{{container=\"{}\"}} {}",
+                    escape(name),
+                    usage
+                );
+            }
+        }
+
+        write_header(
+            &mut result,
+            "docker2mqtt_container_memory_usage_percent",
+            "Container memory usage in percent.",
+        );
+        for (name, container) in self.containers.iter() {
+            if let Some(usage) = container.memory_usage {
+                let _ = writeln!(
+                    result,
+                    "docker2mqtt_container_memory_usage_percent{{container=\"{}\"}} {}",
+                    escape(name),
+                    usage
+                );
+            }
+        }
+
+        write_header(
+            &mut result,
+            "docker2mqtt_container_state",
+            "Current container state, labeled by state.",
+        );
+        for (name, container) in self.containers.iter() {
+            if let Some(state) = container.state {
+                let _ = writeln!(
+                    result,
+                    "docker2mqtt_container_state{{container=\"{}\",state=\"{}\"}} 1",
+                    escape(name),
+                    state
+                );
+            }
+        }
+
+        result
+    }
+
+    fn get_container(&mut self, event: &Event) -> &mut Container {
+        self.containers
+            .entry(event.container_name.to_owned())
+            .or_default()
+    }
+}
+
+fn get_state(container_event: &ContainerEvent) -> Option<&'static str> {
+    match container_event {
+        ContainerEvent::Create => Some("created"),
+        ContainerEvent::Die => Some("dead"),
+        ContainerEvent::Kill | ContainerEvent::Stop => Some("exited"),
+        ContainerEvent::Pause => Some("paused"),
+        ContainerEvent::Restart => Some("restarting"),
+        ContainerEvent::Start | ContainerEvent::Unpause => Some("running"),
+        _ => None,
+    }
+}
+
+fn write_header(result: &mut String, name: &str, help: &str) {
+    let _ = writeln!(result, "# HELP {} {}", name, help);
+    let _ = writeln!(result, "# TYPE {} gauge", name);
+}
+
+fn escape(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod must {
+    use crate::events::{ContainerEvent, Event, EventType, HOST};
+
+    use super::Registry;
+
+    fn create_event(container_name: &str, event: EventType) -> Event {
+        Event {
+            container_name: container_name.to_owned(),
+            event,
+        }
+    }
+
+    #[test]
+    fn render_container_gauges() {
+        // arrange
+        let mut registry = Registry::default();
+        registry.update(&create_event(
+            "nginx",
+            EventType::State(ContainerEvent::Start),
+        ));
+        registry.update(&create_event("nginx", EventType::CpuUsage(12.5)));
+        registry.update(&create_event("nginx", EventType::MemoryUsage(3.0)));
+
+        // act
+        let rendered = registry.render();
+
+        // assert
+        assert!(rendered
+            .contains("docker2mqtt_container_cpu_usage_percent{container=\"nginx\"} 12.5\n"));
+        assert!(rendered
+            .contains("docker2mqtt_container_memory_usage_percent{container=\"nginx\"} 3\n"));
+        assert!(rendered
+            .contains("docker2mqtt_container_state{container=\"nginx\",state=\"running\"} 1\n"));
+    }
+
+    #[test]
+    fn drop_destroyed_containers_and_pseudo_devices() {
+        // arrange
+        let mut registry = Registry::default();
+        registry.update(&create_event("nginx", EventType::CpuUsage(12.5)));
+        registry.update(&create_event(
+            HOST,
+            EventType::State(ContainerEvent::Create),
+        ));
+
+        // act
+        registry.update(&create_event(
+            "nginx",
+            EventType::State(ContainerEvent::Destroy),
+        ));
+
+        // assert
+        assert!(registry.containers.is_empty());
+    }
+
+    #[test]
+    fn clear_usage_of_stopped_containers() {
+        // arrange
+        let mut registry = Registry::default();
+        registry.update(&create_event("nginx", EventType::CpuUsage(12.5)));
+        registry.update(&create_event("nginx", EventType::MemoryUsage(3.0)));
+
+        // act
+        registry.update(&create_event(
+            "nginx",
+            EventType::State(ContainerEvent::Stop),
+        ));
+
+        // assert
+        let rendered = registry.render();
+        assert!(!rendered.contains("docker2mqtt_container_cpu_usage_percent{"));
+        assert!(!rendered.contains("docker2mqtt_container_memory_usage_percent{"));
+        assert!(rendered
+            .contains("docker2mqtt_container_state{container=\"nginx\",state=\"exited\"} 1\n"));
+    }
+
+    #[test]
+    fn escape_label_values() {
+        // act
+        let escaped = super::escape("a\"b\\c");
+
+        // assert
+        assert_eq!("a\\\"b\\\\c", escaped);
+    }
+}
@@ -1,9 +1,10 @@
 use core::panic;
-use rumqttc::{AsyncClient, Event, EventLoop, Incoming, MqttOptions, QoS};
+use rumqttc::{AsyncClient, Event, EventLoop, Incoming, MqttOptions, Outgoing, Publish, QoS};
 use std::time::Duration;
+use tokio::sync::mpsc;
 use tracing::{error, instrument, trace};
 
-use crate::configuration::Configuration;
+use crate::{configuration::Configuration, health, metrics};
 
 use super::message::Message;
 
@@ -41,9 +42,42 @@ impl MqttClient {
             .await;
 
         if let Err(e) = tkn {
+            metrics::publish_failed();
             error!("could not publish to mqtt broker: {}", e);
         }
     }
+
+    // query replies and event entities are meant for the moment only and thus not retained
+    pub async fn send_transient_message(&self, message: Message, conf: &Configuration) {
+        let tkn = &self
+            .client
+            .publish(message.topic, get_qos(conf), false, message.payload)
+            .await;
+
+        if let Err(e) = tkn {
+            metrics::publish_failed();
+            error!("could not publish to mqtt broker: {}", e);
+        }
+    }
+
+    pub async fn subscribe(&self, topic: &str) {
+        if let Err(e) = self.client.subscribe(topic, QoS::AtMostOnce).await {
+            error!("could not subscribe to {}: {}", topic, e);
+        }
+    }
+
+    pub async fn unsubscribe(&self, topic: &str) {
+        if let Err(e) = self.client.unsubscribe(topic).await {
+            error!("could not unsubscribe from {}: {}", topic, e);
+        }
+    }
+
+    // queued after pending publishes, the loop ends once it is sent
+    pub async fn disconnect(&self) {
+        if let Err(e) = self.client.disconnect().await {
+            error!("could not disconnect from mqtt broker: {}", e);
+        }
+    }
 }
 
 fn set_credentials(conf: &Configuration, options: &mut MqttOptions) {
@@ -65,15 +99,30 @@ pub struct MqttLoop {
 }
 
 impl MqttLoop {
+    // every connection starts without subscriptions, thus the publish task gets told to subscribe
     #[instrument(skip(self))]
-    pub async fn start_loop(mut self) {
+    pub async fn start_loop(
+        mut self,
+        incoming_sender: mpsc::UnboundedSender<Publish>,
+        connected_sender: mpsc::UnboundedSender<()>,
+    ) {
         loop {
             match self.eventloop.poll().await {
+                Ok(Event::Incoming(Incoming::ConnAck(_))) => {
+                    health::set_mqtt_connected(true);
+                    let _ = connected_sender.send(());
+                }
+                Ok(Event::Outgoing(Outgoing::Disconnect)) => {
+                    health::set_mqtt_connected(false);
+                    return;
+                }
                 Ok(Event::Incoming(Incoming::Publish(p))) => {
-                    trace!("incoming publish mqtt event: {}, {:?}", p.topic, p.payload)
+                    trace!("incoming publish mqtt event: {}, {:?}", p.topic, p.payload);
+                    let _ = incoming_sender.send(p);
                 }
                 Ok(_) => {}
                 Err(e) => {
+                    health::set_mqtt_connected(false);
                     error!("could not connect to mqtt broker: {}", e);
                     continue;
                 }
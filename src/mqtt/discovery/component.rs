@@ -0,0 +1,60 @@
+use std::fmt;
+
+use crate::events::EventType;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Component {
+    BinarySensor,
+    Event,
+    Sensor,
+    Update,
+}
+
+impl fmt::Display for Component {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let value = match self {
+            Component::BinarySensor => "binary_sensor",
+            Component::Event => "event",
+            Component::Sensor => "sensor",
+            Component::Update => "update",
+        };
+
+        write!(f, "{}", value)
+    }
+}
+
+pub fn get(sensor: &EventType) -> Component {
+    match sensor {
+        EventType::Reachable(_) | EventType::UpdateAvailable(_) => Component::BinarySensor,
+        EventType::LogAlert(_) => Component::Event,
+        EventType::Update(_) => Component::Update,
+        _ => Component::Sensor,
+    }
+}
+
+pub fn get_device_class(sensor: &EventType) -> Option<String> {
+    match sensor {
+        EventType::BuildCacheSize(_)
+        | EventType::ImagesSize(_)
+        | EventType::TotalMemory(_)
+        | EventType::VolumesSize(_) => Some("data_size".to_owned()),
+        EventType::Downtime(_) | EventType::TimeSinceStart(_) => Some("duration".to_owned()),
+        EventType::Reachable(_) => Some("connectivity".to_owned()),
+        EventType::UpdateAvailable(_) => Some("update".to_owned()),
+        _ => None,
+    }
+}
+
+pub fn get_unit_of_measurement(sensor: &EventType) -> Option<String> {
+    match sensor {
+        EventType::BuildCacheSize(_)
+        | EventType::ImagesSize(_)
+        | EventType::TotalMemory(_)
+        | EventType::VolumesSize(_) => Some("B".to_owned()),
+        EventType::Downtime(_) | EventType::TimeSinceStart(_) => Some("s".to_owned()),
+        EventType::Uptime24h(_) | EventType::Uptime30d(_) | EventType::Uptime7d(_) => {
+            Some("%".to_owned())
+        }
+        _ => None,
+    }
+}
@@ -1,9 +1,13 @@
 use tracing::instrument;
 
-use crate::configuration::{Configuration, Hassio};
+use crate::{
+    configuration::{Configuration, Hassio},
+    events::{EventType, EVENT_NAMES, HOST},
+};
 
 use super::topic;
 
+mod component;
 mod payload;
 
 pub type HassioResult<T> = Result<T, HassioErr>;
@@ -14,25 +18,66 @@ pub enum HassioErr {
 }
 
 #[instrument(level = "debug")]
-pub fn topic(container_name: &str, event_name: &str, conf: &Configuration) -> HassioResult<String> {
+pub fn topic(
+    container_name: &str,
+    sensor: &EventType,
+    conf: &Configuration,
+) -> HassioResult<String> {
     let hassio = get_hassio(conf)?;
-    let unique_id = payload::get_unique_id(conf, hassio, container_name, event_name);
+    let unique_id = payload::get_unique_id(conf, hassio, container_name, &sensor.to_string());
 
     Ok(format!(
-        "{}/sensor/docker2mqtt/{}/config",
-        hassio.discovery_prefix, unique_id
+        "{}/{}/docker2mqtt/{}/config",
+        hassio.discovery_prefix,
+        component::get(sensor),
+        unique_id
     ))
 }
 
 #[instrument(level = "debug")]
 pub fn payload(
     container_name: &str,
-    event_name: &str,
+    sensor: &EventType,
     conf: &Configuration,
 ) -> HassioResult<String> {
     let hassio = get_hassio(conf)?;
 
-    Ok(payload::create(container_name, event_name, conf, hassio))
+    Ok(payload::create(container_name, sensor, conf, hassio))
+}
+
+pub fn tree(conf: &Configuration) -> Option<String> {
+    let hassio = get_hassio(conf).ok()?;
+
+    Some(format!(
+        "{}/+/docker2mqtt/+/config",
+        hassio.discovery_prefix
+    ))
+}
+
+// splits a unique id of this instance into device and event name, the longest event name wins as
+// some are suffixes of others, e.g. state and task_state
+pub fn parse_unique_id<'a>(
+    unique_id: &'a str,
+    conf: &Configuration,
+) -> Option<(&'a str, &'static str)> {
+    let hassio = get_hassio(conf).ok()?;
+    let rest = unique_id
+        .strip_prefix(hassio.device_prefix.as_str())?
+        .strip_prefix('_')?
+        .strip_prefix(conf.mqtt.client_id.as_str())?
+        .strip_prefix('_')?;
+
+    EVENT_NAMES
+        .iter()
+        .filter_map(|name| match rest.strip_suffix(name) {
+            Some("") => Some((HOST, *name)),
+            Some(device) => device
+                .strip_suffix('_')
+                .filter(|device| !device.is_empty())
+                .map(|device| (device, *name)),
+            None => None,
+        })
+        .max_by_key(|(_, name)| name.len())
 }
 
 fn get_hassio(conf: &Configuration) -> HassioResult<&Hassio> {
@@ -2,21 +2,35 @@ use serde::Serialize;
 
 use crate::{
     configuration::{Configuration, Hassio},
-    mqtt::availability::Availability,
+    events::{self, EventType, HOST},
+    mqtt::{availability::Availability, payload},
 };
 
-use super::topic;
+use super::{component, topic};
 
 #[derive(Serialize)]
 pub struct HassioEvent {
     pub availability_topic: String,
     pub device: HassioDevice,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub device_class: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub event_types: Option<Vec<String>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub json_attributes_topic: Option<String>,
+
     pub name: String,
     pub payload_available: String,
     pub payload_not_available: String,
     pub platform: String,
     pub state_topic: String,
     pub unique_id: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub unit_of_measurement: Option<String>,
 }
 
 #[derive(Serialize)]
@@ -25,14 +39,18 @@ pub struct HassioDevice {
     pub manufacturer: String,
     pub model: String,
     pub name: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub via_device: Option<String>,
 }
 
 pub fn create(
     container_name: &str,
-    event_name: &str,
+    sensor: &EventType,
     conf: &Configuration,
     hassio: &Hassio,
 ) -> String {
+    let event_name = &sensor.to_string();
     let device_name = get_device_name(conf, hassio, container_name);
     let unique_id = get_unique_id(conf, hassio, container_name, event_name);
     let identifiers = vec![device_name.to_string()];
@@ -42,21 +60,64 @@ pub fn create(
         device: HassioDevice {
             identifiers,
             manufacturer: "docker2mqtt".to_string(),
-            model: "docker".to_string(),
+            model: get_model(container_name),
             name: device_name,
+            via_device: get_via_device(conf, hassio, container_name),
         },
+        device_class: component::get_device_class(sensor),
+        event_types: get_event_types(conf, container_name, sensor),
+        json_attributes_topic: payload::attributes(sensor)
+            .map(|_| topic::attributes(container_name, event_name, conf)),
         name: unique_id.to_string(),
         payload_available: Availability::Online.to_string(),
         payload_not_available: Availability::Offline.to_string(),
         platform: "mqtt".to_string(),
         state_topic: topic::state(container_name, event_name, conf),
         unique_id,
+        unit_of_measurement: component::get_unit_of_measurement(sensor),
     };
 
     serde_json::to_string(&event).unwrap()
 }
 
+fn get_event_types(
+    conf: &Configuration,
+    container_name: &str,
+    sensor: &EventType,
+) -> Option<Vec<String>> {
+    match sensor {
+        EventType::LogAlert(_) => Some(
+            conf.docker
+                .stream_logs_alerts
+                .get(container_name)
+                .map(|alerts| alerts.iter().map(|a| a.name.to_owned()).collect())
+                .unwrap_or_default(),
+        ),
+        _ => None,
+    }
+}
+
+fn get_model(container_name: &str) -> String {
+    match container_name {
+        HOST => "docker host".to_string(),
+        name if events::is_service(name) => "docker service".to_string(),
+        _ => "docker".to_string(),
+    }
+}
+
+fn get_via_device(conf: &Configuration, hassio: &Hassio, container_name: &str) -> Option<String> {
+    if container_name == HOST || (conf.host.is_none() && conf.swarm.is_none()) {
+        return None;
+    }
+
+    Some(get_device_name(conf, hassio, HOST))
+}
+
 fn get_device_name(conf: &Configuration, hassio: &Hassio, container_name: &str) -> String {
+    if container_name == HOST {
+        return format!("{}_{}", hassio.device_prefix, conf.mqtt.client_id);
+    }
+
     format!(
         "{}_{}_{}",
         hassio.device_prefix, conf.mqtt.client_id, container_name
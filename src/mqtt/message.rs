@@ -2,7 +2,9 @@ use tracing::warn;
 
 use crate::{
     configuration::Configuration,
-    events::{ContainerEvent, Event, EventType},
+    events::{
+        self, Alert, ContainerEvent, Event, EventType, ImageUpdate, LogLine, TaskTransition, HOST,
+    },
 };
 
 use super::{availability, discovery, payload, topic};
@@ -14,6 +16,16 @@ pub struct Message {
 }
 
 pub fn get_event_messages(event: Event, conf: &Configuration) -> Vec<Message> {
+    if let EventType::Orphaned(topics) = &event.event {
+        return topics
+            .iter()
+            .map(|topic| Message {
+                topic: topic.to_owned(),
+                payload: "".to_owned(),
+            })
+            .collect();
+    }
+
     let mut messages = vec![];
 
     for message in get_discovery(&event, conf) {
@@ -34,17 +46,98 @@ pub fn get_event_messages(event: Event, conf: &Configuration) -> Vec<Message> {
         payload: payload::get(&event),
     });
 
+    if let Some(attributes) = payload::attributes(&event.event) {
+        messages.push(Message {
+            topic: topic::attributes(&event.container_name, &event.event.to_string(), conf),
+            payload: attributes,
+        });
+    }
+
     messages
 }
 
+// topics published without retain, home assistant event entities would replay retained messages as
+// new events on every restart or reconnect
+pub fn get_transient_topics(event: &Event, conf: &Configuration) -> Vec<String> {
+    match &event.event {
+        EventType::LogAlert(_) => vec![topic::state(
+            &event.container_name,
+            &event.event.to_string(),
+            conf,
+        )],
+        _ => vec![],
+    }
+}
+
+// topics which keep a payload on the broker after publishing the messages of the event
+pub fn get_retained_topics(event: &Event, conf: &Configuration) -> Vec<String> {
+    let container_name = &event.container_name;
+    let event_name = event.event.to_string();
+
+    let mut topics = match &event.event {
+        EventType::LogAlert(_)
+        | EventType::Orphaned(_)
+        | EventType::State(ContainerEvent::Destroy) => return vec![],
+        EventType::State(ContainerEvent::Create) => get_discovery_topics(container_name, conf),
+        _ => vec![],
+    };
+
+    if let EventType::State(_) = &event.event {
+        topics.push(topic::availability(container_name, conf));
+    }
+
+    topics.push(topic::state(container_name, &event_name, conf));
+
+    if payload::attributes(&event.event).is_some() {
+        topics.push(topic::attributes(container_name, &event_name, conf));
+    }
+
+    topics
+}
+
+// clears discovery of dropped sensors and announces the sensors of the current configuration
+pub fn get_rediscovery_messages(
+    container_name: &str,
+    previous: &Configuration,
+    conf: &Configuration,
+) -> Vec<Message> {
+    let event = |container_event| Event {
+        container_name: container_name.to_owned(),
+        event: EventType::State(container_event),
+    };
+
+    let current = get_discovery(&event(ContainerEvent::Create), conf);
+    let mut messages: Vec<Message> = get_discovery(&event(ContainerEvent::Destroy), previous)
+        .into_iter()
+        .filter(|message| !current.iter().any(|c| c.topic == message.topic))
+        .collect();
+
+    messages.extend(current);
+    messages
+}
+
+// marks the devices offline, as nothing gets updated once docker2mqtt is gone
+pub fn get_offline_messages<'a>(
+    devices: impl Iterator<Item = &'a String>,
+    conf: &Configuration,
+) -> Vec<Message> {
+    devices
+        .map(|device| Message {
+            topic: topic::availability(device, conf),
+            payload: availability::Availability::Offline.to_string(),
+        })
+        .collect()
+}
+
+pub fn get_discovery_topics(container_name: &str, conf: &Configuration) -> Vec<String> {
+    get_sensors(container_name, conf)
+        .iter()
+        .filter_map(|sensor| discovery::topic(container_name, sensor, conf).ok())
+        .collect()
+}
+
 fn get_discovery(event: &Event, conf: &Configuration) -> Vec<Message> {
-    let sensors = vec![
-        EventType::CpuUsage(0.0),
-        EventType::Image("".to_owned()),
-        EventType::Log("".to_owned()),
-        EventType::MemoryUsage(0.0),
-        EventType::State(ContainerEvent::Create),
-    ];
+    let sensors = get_sensors(&event.container_name, conf);
 
     let mut result = vec![];
 
@@ -56,15 +149,126 @@ fn get_discovery(event: &Event, conf: &Configuration) -> Vec<Message> {
     result
 }
 
+fn get_sensors(container_name: &str, conf: &Configuration) -> Vec<EventType> {
+    match container_name {
+        HOST => get_host_sensors(conf),
+        name if events::is_service(name) => get_service_sensors(),
+        name => get_container_sensors(name, conf),
+    }
+}
+
+fn get_container_sensors(container_name: &str, conf: &Configuration) -> Vec<EventType> {
+    let mut sensors = vec![
+        EventType::CpuUsage(0.0),
+        EventType::Image("".to_owned()),
+        EventType::MemoryUsage(0.0),
+        EventType::State(ContainerEvent::Create),
+    ];
+
+    if conf.updates.is_some() {
+        sensors.push(EventType::Update(ImageUpdate::default()));
+        sensors.push(EventType::UpdateAvailable(false));
+    }
+
+    if conf.docker.stream_logs_split {
+        sensors.push(EventType::LogStderr(LogLine::default()));
+        sensors.push(EventType::LogStdout(LogLine::default()));
+    } else {
+        sensors.push(EventType::Log(LogLine::default()));
+    }
+
+    if conf.docker.stream_logs_alerts.contains_key(container_name) {
+        sensors.push(EventType::LastLogAlert(None));
+        sensors.push(EventType::LogAlert(Alert::default()));
+    }
+
+    if conf.docker.stream_logs_rate > 0.0 {
+        sensors.push(EventType::LogsSuppressed(0));
+    }
+
+    if !conf.docker.stats_windows.is_empty() {
+        sensors.push(EventType::CpuStatistics(vec![]));
+        sensors.push(EventType::MemoryStatistics(vec![]));
+    }
+
+    if conf.docker.track_networks {
+        sensors.push(EventType::Networks(vec![]));
+    }
+
+    if conf.docker.track_uptime {
+        sensors.push(EventType::Downtime(0));
+        sensors.push(EventType::TimeSinceStart(0));
+        sensors.push(EventType::Uptime24h(0.0));
+        sensors.push(EventType::Uptime30d(0.0));
+        sensors.push(EventType::Uptime7d(0.0));
+    }
+
+    if conf.docker.track_volumes {
+        sensors.push(EventType::Volumes(vec![]));
+    }
+
+    sensors
+}
+
+fn get_service_sensors() -> Vec<EventType> {
+    vec![
+        EventType::DesiredReplicas(0),
+        EventType::RunningReplicas(0),
+        EventType::TaskState(TaskTransition::default()),
+    ]
+}
+
+fn get_host_sensors(conf: &Configuration) -> Vec<EventType> {
+    let mut sensors = vec![];
+
+    if conf.host.is_some() {
+        sensors.append(&mut get_inventory_sensors(conf));
+    }
+
+    if conf.swarm.is_some() {
+        sensors.push(EventType::SwarmNodes(vec![]));
+    }
+
+    sensors
+}
+
+fn get_inventory_sensors(conf: &Configuration) -> Vec<EventType> {
+    let mut sensors = vec![
+        EventType::BuildCacheSize(0),
+        EventType::ContainersPaused(0),
+        EventType::ContainersRunning(0),
+        EventType::ContainersStopped(0),
+        EventType::Cpus(0),
+        EventType::DanglingImages(0),
+        EventType::EngineVersion("".to_owned()),
+        EventType::Images(0),
+        EventType::ImagesSize(0),
+        EventType::KernelVersion("".to_owned()),
+        EventType::OperatingSystem("".to_owned()),
+        EventType::Reachable(false),
+        EventType::TotalMemory(0),
+        EventType::VolumesSize(0),
+    ];
+
+    if conf.docker.track_networks {
+        sensors.push(EventType::UnusedNetworks(vec![]));
+    }
+
+    if conf.docker.track_volumes {
+        sensors.push(EventType::UnusedVolumes(vec![]));
+    }
+
+    sensors
+}
+
 fn get_discovery_message(
     event: &Event,
     sensor: &EventType,
     conf: &Configuration,
 ) -> Option<Message> {
     let container_name = &event.container_name;
-    let event_name = &sensor.to_string();
 
-    let topic = match discovery::topic(container_name, event_name, conf) {
+    let topic = match discovery::topic(container_name, sensor, conf) {
         Ok(topic) => topic,
         Err(e) => {
             warn!("could not resolve discovery topic: {:?}", e);
@@ -72,7 +276,7 @@ fn get_discovery_message(
         }
     };
 
-    let payload = match discovery::payload(container_name, event_name, conf) {
+    let payload = match discovery::payload(container_name, sensor, conf) {
         Ok(payload) => payload,
         Err(e) => {
             warn!("could not resolve discovery payload: {:?}", e);
@@ -89,3 +293,46 @@ fn get_discovery_message(
         _ => None,
     }
 }
+
+#[cfg(test)]
+mod must {
+    use crate::events::{Alert, Event, EventType};
+
+    use super::{get_retained_topics, get_transient_topics};
+
+    fn create_configuration() -> super::Configuration {
+        serde_yaml::from_str(
+            "
+mqtt:
+  client_id: qwert
+  host: yuio
+  port: 1234",
+        )
+        .unwrap()
+    }
+
+    fn create_event(event: EventType) -> Event {
+        Event {
+            container_name: "borg".to_owned(),
+            event,
+        }
+    }
+
+    #[test]
+    fn publish_log_alert_events_without_retain() {
+        // arrange
+        let conf = create_configuration();
+        let alert = create_event(EventType::LogAlert(Alert::default()));
+        let last_alert = create_event(EventType::LastLogAlert(None));
+
+        // act
+        let transient = get_transient_topics(&alert, &conf);
+
+        // assert
+        assert_eq!(vec!["docker2mqtt/qwert/borg/log_alert/state"], transient);
+        assert!(get_retained_topics(&alert, &conf).is_empty());
+        assert!(get_transient_topics(&last_alert, &conf).is_empty());
+        assert!(get_retained_topics(&last_alert, &conf)
+            .contains(&"docker2mqtt/qwert/borg/last_log_alert/state".to_owned()));
+    }
+}
@@ -1,42 +1,238 @@
+use std::collections::HashSet;
+
+use rumqttc::Publish;
 use tokio::{
-    sync::broadcast::{error::RecvError, Receiver},
+    sync::mpsc,
     task,
+    time::{self, Instant},
 };
-use tracing::{error, instrument};
+use tracing::{info, instrument};
 
-use crate::{configuration::Configuration, events::Event};
+use crate::{
+    bus::Subscriber,
+    configuration::Configuration,
+    events::{ContainerEvent, Event, EventType},
+    health,
+    persistence::SharedRepository,
+    reload::Reloads,
+};
 
-use self::client::MqttClient;
+use self::{client::MqttClient, message::Message, retained::Retained};
 
 mod availability;
 mod client;
 mod discovery;
 mod message;
 mod payload;
+mod query;
+mod retained;
+mod sweep;
 mod topic;
 
-pub async fn task(mut receiver: Receiver<Event>, conf: &Configuration) {
-    let (mqtt_client, mqtt_loop) = MqttClient::new(conf).await;
-    let conf_for_move = conf.clone();
+pub async fn task(mut subscriber: Subscriber, mut reloads: Reloads, repo: SharedRepository) {
+    let mut conf = reloads.borrow().clone();
+    let (mqtt_client, mqtt_loop) = MqttClient::new(&conf).await;
+    let (incoming_sender, mut incoming_receiver) = mpsc::unbounded_channel::<Publish>();
+    let (connected_sender, mut connected_receiver) = mpsc::unbounded_channel::<()>();
+
+    let subscriptions = sweep::get_subscriptions(&conf);
+    let query_topic = topic::query(&conf);
 
     task::spawn(async move {
+        let _alive = health::alive("mqtt_publish");
+        let mut devices = HashSet::new();
+        let mut retained = Retained::default();
+
+        let sweep_deadline = time::sleep_until(Instant::now() + sweep::SWEEP_DELAY);
+        tokio::pin!(sweep_deadline);
+        let mut candidates = vec![];
+        let mut swept = false;
+
         loop {
-            match receiver.recv().await {
-                Ok(event) => send_event_messages(&mqtt_client, event, &conf_for_move).await,
-                Err(RecvError::Closed) => break,
-                Err(RecvError::Lagged(m)) => error!("Receiver lagging. Skipped {} messages", m),
+            tokio::select! {
+                event = subscriber.recv() => match event {
+                    Some(event) => {
+                        track_device(&event, &mut devices);
+                        send_event_messages(&mqtt_client, event, &conf, &mut retained).await;
+                    }
+                    None => break,
+                },
+                Ok(()) = reloads.changed() => {
+                    let current = reloads.borrow().clone();
+                    send_rediscovery_messages(&mqtt_client, &devices, &conf, &current, &mut retained)
+                        .await;
+                    conf = current;
+                }
+                Some(publish) = incoming_receiver.recv() => {
+                    if publish.topic == query_topic && conf.history.is_some() {
+                        let reply = query::get_reply(&publish.payload, &repo, &conf);
+                        mqtt_client.send_transient_message(reply, &conf).await;
+                    } else if !swept && publish.retain && !publish.payload.is_empty() {
+                        // the broker flags stored messages only, live messages arrive without retain
+                        if !candidates.contains(&publish.topic) {
+                            candidates.push(publish.topic);
+                        }
+                    }
+                }
+                Some(()) = connected_receiver.recv() => {
+                    for subscription in get_connect_subscriptions(&subscriptions, swept, &query_topic, &conf) {
+                        mqtt_client.subscribe(&subscription).await;
+                    }
+                }
+                _ = &mut sweep_deadline, if !swept => {
+                    swept = true;
+
+                    for subscription in subscriptions.iter() {
+                        mqtt_client.unsubscribe(subscription).await;
+                    }
+
+                    let stale = sweep::get_stale_topics(&candidates, &devices, &retained, &conf);
+                    info!("removing {} retained topics without live device", stale.len());
+
+                    for topic in stale.into_iter() {
+                        let message = Message { topic, payload: "".to_owned() };
+                        mqtt_client.send_message(message, &conf).await;
+                    }
+
+                    candidates.clear();
+                }
             }
         }
+
+        // the bus closes on shutdown once every event is published
+        info!("marking {} devices offline", devices.len());
+        for message in message::get_offline_messages(devices.iter(), &conf) {
+            mqtt_client.send_message(message, &conf).await;
+        }
+
+        mqtt_client.disconnect().await;
     });
 
-    mqtt_loop.start_loop().await;
+    mqtt_loop
+        .start_loop(incoming_sender, connected_sender)
+        .await;
+}
+
+pub fn get_discovery_topics(container_name: &str, conf: &Configuration) -> Vec<String> {
+    message::get_discovery_topics(container_name, conf)
 }
 
-#[instrument(level = "debug")]
-async fn send_event_messages(mqtt_client: &MqttClient, event: Event, conf: &Configuration) {
-    let messages = message::get_event_messages(event, conf);
+pub fn get_retained_topics(event: &Event, conf: &Configuration) -> Vec<String> {
+    message::get_retained_topics(event, conf)
+}
+
+#[instrument(level = "debug", skip(retained))]
+async fn send_event_messages(
+    mqtt_client: &MqttClient,
+    event: Event,
+    conf: &Configuration,
+    retained: &mut Retained,
+) {
+    let container_name = event.container_name.to_owned();
+    let destroyed = event.event == EventType::State(ContainerEvent::Destroy);
+    let transient = message::get_transient_topics(&event, conf);
+
+    let mut messages = message::get_event_messages(event, conf);
+    for message in messages.iter() {
+        if !transient.contains(&message.topic) {
+            retained.track(&container_name, message);
+        }
+    }
+
+    // removed containers leave nothing behind on the broker
+    if destroyed {
+        messages.extend(retained.clear(&container_name));
+    }
 
     for message in messages.into_iter() {
-        mqtt_client.send_message(message, conf).await;
+        match transient.contains(&message.topic) {
+            true => mqtt_client.send_transient_message(message, conf).await,
+            false => mqtt_client.send_message(message, conf).await,
+        }
+    }
+}
+
+async fn send_rediscovery_messages(
+    mqtt_client: &MqttClient,
+    devices: &HashSet<String>,
+    previous: &Configuration,
+    conf: &Configuration,
+    retained: &mut Retained,
+) {
+    for device in devices.iter() {
+        for message in message::get_rediscovery_messages(device, previous, conf) {
+            retained.track(device, &message);
+            mqtt_client.send_message(message, conf).await;
+        }
+    }
+}
+
+// the broker drops subscriptions with the clean session, thus they are renewed on every connect
+fn get_connect_subscriptions(
+    sweep_subscriptions: &[String],
+    swept: bool,
+    query_topic: &str,
+    conf: &Configuration,
+) -> Vec<String> {
+    let mut subscriptions = match swept {
+        true => vec![],
+        false => sweep_subscriptions.to_vec(),
+    };
+
+    if conf.history.is_some() {
+        subscriptions.push(query_topic.to_owned());
+    }
+
+    subscriptions
+}
+
+fn track_device(event: &Event, devices: &mut HashSet<String>) {
+    match &event.event {
+        EventType::State(ContainerEvent::Create) => {
+            devices.insert(event.container_name.to_owned());
+        }
+        EventType::State(ContainerEvent::Destroy) => {
+            devices.remove(&event.container_name);
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod must {
+    use super::get_connect_subscriptions;
+
+    #[test]
+    fn renew_query_subscription_after_sweep() {
+        // arrange
+        let conf: super::Configuration = serde_yaml::from_str(
+            "
+history:
+  limit: 10
+
+mqtt:
+  client_id: qwert
+  host: yuio
+  port: 1234",
+        )
+        .unwrap();
+        let sweep_subscriptions = vec!["docker2mqtt/qwert/#".to_owned()];
+
+        // act
+        let sweeping = get_connect_subscriptions(
+            &sweep_subscriptions,
+            false,
+            "docker2mqtt/qwert/query",
+            &conf,
+        );
+        let swept =
+            get_connect_subscriptions(&sweep_subscriptions, true, "docker2mqtt/qwert/query", &conf);
+
+        // assert
+        assert_eq!(
+            vec!["docker2mqtt/qwert/#", "docker2mqtt/qwert/query"],
+            sweeping
+        );
+        assert_eq!(vec!["docker2mqtt/qwert/query"], swept);
     }
 }
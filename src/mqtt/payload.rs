@@ -1,12 +1,72 @@
-use crate::events::{ContainerEvent, Event, EventType};
+use serde_json::{json, Map, Value};
+
+use crate::events::{
+    Alert, ContainerEvent, Event, EventType, ImageUpdate, Statistics, SwarmNode, TaskTransition,
+};
 
 pub fn get(event: &Event) -> String {
     match &event.event {
+        EventType::BuildCacheSize(size) => size.to_string(),
+        EventType::ContainerId(id) => id.to_owned(),
+        EventType::ContainersPaused(count) => count.to_string(),
+        EventType::ContainersRunning(count) => count.to_string(),
+        EventType::ContainersStopped(count) => count.to_string(),
+        EventType::CpuStatistics(statistics) => get_statistics_payload(statistics),
         EventType::CpuUsage(usage) => format!("{:.2}", usage),
+        EventType::Cpus(count) => count.to_string(),
+        EventType::DanglingImages(count) => count.to_string(),
+        EventType::DesiredReplicas(count) => count.to_string(),
+        EventType::Downtime(seconds) => seconds.to_string(),
+        EventType::EngineVersion(version) => version.to_owned(),
+        EventType::ExitCode(code) => code.to_string(),
         EventType::Image(image) => image.to_owned(),
-        EventType::Log(log) => log.to_owned(),
+        EventType::Images(count) => count.to_string(),
+        EventType::ImagesSize(size) => size.to_string(),
+        EventType::KernelVersion(version) => version.to_owned(),
+        EventType::LastLogAlert(alert) => get_last_alert_payload(alert.as_ref()).to_owned(),
+        EventType::Log(line) | EventType::LogStderr(line) | EventType::LogStdout(line) => {
+            line.message.to_owned()
+        }
+        EventType::LogAlert(alert) => get_alert_payload(alert),
+        EventType::LogsSuppressed(count) => count.to_string(),
+        EventType::MemoryStatistics(statistics) => get_statistics_payload(statistics),
         EventType::MemoryUsage(usage) => format!("{:.2}", usage),
+        EventType::Networks(networks) => networks.join(", "),
+        EventType::OperatingSystem(os) => os.to_owned(),
+        EventType::Orphaned(topics) => topics.join(", "),
+        EventType::Reachable(reachable) => get_binary_payload(*reachable).to_owned(),
+        EventType::RunningReplicas(count) => count.to_string(),
         EventType::State(event) => get_status_payload(event).to_owned(),
+        EventType::SwarmNodes(nodes) => get_available_nodes(nodes).to_string(),
+        EventType::TaskState(transition) => transition.state.to_owned(),
+        EventType::TimeSinceStart(seconds) => seconds.to_string(),
+        EventType::TotalMemory(size) => size.to_string(),
+        EventType::Update(update) => get_update_payload(update),
+        EventType::UnusedNetworks(networks) => networks.len().to_string(),
+        EventType::UnusedVolumes(volumes) => volumes.len().to_string(),
+        EventType::UpdateAvailable(available) => get_binary_payload(*available).to_owned(),
+        EventType::Uptime24h(ratio) | EventType::Uptime30d(ratio) | EventType::Uptime7d(ratio) => {
+            format!("{:.2}", ratio)
+        }
+        EventType::Volumes(volumes) => volumes.join(", "),
+        EventType::VolumesSize(size) => size.to_string(),
+    }
+}
+
+pub fn attributes(event: &EventType) -> Option<String> {
+    match event {
+        EventType::CpuStatistics(statistics) | EventType::MemoryStatistics(statistics) => {
+            Some(get_statistics_attributes(statistics))
+        }
+        EventType::UnusedNetworks(networks) => Some(json!({ "networks": networks }).to_string()),
+        EventType::LastLogAlert(alert) => Some(get_last_alert_attributes(alert.as_ref())),
+        EventType::Log(line) | EventType::LogStderr(line) | EventType::LogStdout(line) => {
+            Some(json!({ "stream": line.stream.to_string(), "ts": line.timestamp }).to_string())
+        }
+        EventType::SwarmNodes(nodes) => Some(get_nodes_attributes(nodes)),
+        EventType::TaskState(transition) => Some(get_task_attributes(transition)),
+        EventType::UnusedVolumes(volumes) => Some(json!({ "volumes": volumes }).to_string()),
+        _ => None,
     }
 }
 
@@ -26,3 +86,110 @@ fn get_status_payload(event: &ContainerEvent) -> &str {
         ContainerEvent::Prune => "removing",
     }
 }
+
+fn get_update_payload(update: &ImageUpdate) -> String {
+    json!({
+        "installed_version": update.installed_version,
+        "latest_version": update.latest_version,
+    })
+    .to_string()
+}
+
+fn get_alert_payload(alert: &Alert) -> String {
+    json!({
+        "event_type": alert.name,
+        "severity": alert.severity,
+        "message": alert.message,
+        "ts": alert.timestamp,
+    })
+    .to_string()
+}
+
+fn get_last_alert_payload(alert: Option<&Alert>) -> &str {
+    match alert {
+        Some(alert) => &alert.name,
+        None => "none",
+    }
+}
+
+fn get_last_alert_attributes(alert: Option<&Alert>) -> String {
+    json!({
+        "severity": alert.map(|a| &a.severity),
+        "message": alert.map(|a| &a.message),
+        "ts": alert.and_then(|a| a.timestamp.as_ref()),
+    })
+    .to_string()
+}
+
+fn get_statistics_payload(statistics: &[Statistics]) -> String {
+    match statistics.first() {
+        Some(statistics) => format!("{:.2}", statistics.avg),
+        None => "".to_owned(),
+    }
+}
+
+fn get_statistics_attributes(statistics: &[Statistics]) -> String {
+    let mut attributes = Map::new();
+    for statistics in statistics {
+        attributes.insert(
+            get_window_name(statistics.window),
+            json!({
+                "min": round(statistics.min),
+                "avg": round(statistics.avg),
+                "max": round(statistics.max),
+                "p95": round(statistics.p95),
+            }),
+        );
+    }
+
+    Value::Object(attributes).to_string()
+}
+
+fn get_window_name(window: u64) -> String {
+    match window % 60 {
+        0 => format!("{}m", window / 60),
+        _ => format!("{}s", window),
+    }
+}
+
+fn round(value: f64) -> f64 {
+    (value * 100.0).round() / 100.0
+}
+
+fn get_available_nodes(nodes: &[SwarmNode]) -> usize {
+    nodes
+        .iter()
+        .filter(|node| node.state == "ready" && node.availability == "active")
+        .count()
+}
+
+fn get_nodes_attributes(nodes: &[SwarmNode]) -> String {
+    let nodes: Vec<_> = nodes
+        .iter()
+        .map(|node| {
+            json!({
+                "name": node.name,
+                "state": node.state,
+                "availability": node.availability,
+            })
+        })
+        .collect();
+
+    json!({ "nodes": nodes }).to_string()
+}
+
+fn get_task_attributes(transition: &TaskTransition) -> String {
+    json!({
+        "task": transition.task,
+        "node": transition.node,
+        "previous_state": transition.previous_state,
+    })
+    .to_string()
+}
+
+fn get_binary_payload(value: bool) -> &'static str {
+    match value {
+        true => "ON",
+        false => "OFF",
+    }
+}
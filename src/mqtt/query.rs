@@ -0,0 +1,96 @@
+use serde_json::json;
+
+use crate::{
+    configuration::Configuration,
+    persistence::{self, Query, SharedRepository},
+};
+
+use super::{message::Message, topic};
+
+// replies go to reply_to of the query or the default reply topic
+pub fn get_reply(payload: &[u8], repo: &SharedRepository, conf: &Configuration) -> Message {
+    let query: Query = match serde_json::from_slice(payload) {
+        Ok(query) => query,
+        Err(e) => {
+            return Message {
+                topic: topic::query_reply(conf),
+                payload: json!({ "error": format!("invalid query: {}", e) }).to_string(),
+            }
+        }
+    };
+
+    let entries = persistence::query_history(repo.lock().unwrap().as_ref(), &query);
+
+    Message {
+        topic: query.reply_to.unwrap_or_else(|| topic::query_reply(conf)),
+        payload: json!({ "container": query.container, "entries": entries }).to_string(),
+    }
+}
+
+#[cfg(test)]
+mod must {
+    use std::sync::{Arc, Mutex};
+
+    use crate::persistence::{self, Repository, SharedRepository};
+
+    use super::get_reply;
+
+    fn create_configuration() -> super::Configuration {
+        serde_yaml::from_str(
+            "
+mqtt:
+  client_id: qwert
+  host: yuio
+  port: 1234",
+        )
+        .unwrap()
+    }
+
+    fn create_repository(name: &str) -> SharedRepository {
+        let repo: Box<dyn Repository> =
+            persistence::open(crate::configuration::Backend::Json, &create_dir(name)).unwrap();
+
+        Arc::new(Mutex::new(repo))
+    }
+
+    // tests run in parallel, thus each one gets a directory of its own
+    fn create_dir(name: &str) -> std::path::PathBuf {
+        let dir = std::env::temp_dir().join(format!("d2m_query_{}", name));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+
+        dir
+    }
+
+    #[test]
+    fn reply_on_given_topic() {
+        // arrange
+        let conf = create_configuration();
+        let repo = create_repository("reply_on_given_topic");
+
+        // act
+        let reply = get_reply(
+            br#"{"container": "borg", "reply_to": "ha/borg/history"}"#,
+            &repo,
+            &conf,
+        );
+
+        // assert
+        assert_eq!("ha/borg/history", reply.topic);
+        assert_eq!(r#"{"container":"borg","entries":[]}"#, reply.payload);
+    }
+
+    #[test]
+    fn reply_error_on_invalid_query() {
+        // arrange
+        let conf = create_configuration();
+        let repo = create_repository("reply_error_on_invalid_query");
+
+        // act
+        let reply = get_reply(b"borg", &repo, &conf);
+
+        // assert
+        assert_eq!("docker2mqtt/qwert/query/reply", reply.topic);
+        assert!(reply.payload.starts_with(r#"{"error":"invalid query"#));
+    }
+}
@@ -0,0 +1,69 @@
+use std::collections::{BTreeSet, HashMap};
+
+use super::message::Message;
+
+// every topic published with retain stays on the broker until an empty payload gets published
+#[derive(Debug, Default)]
+pub struct Retained {
+    topics: HashMap<String, BTreeSet<String>>,
+}
+
+impl Retained {
+    pub fn track(&mut self, container_name: &str, message: &Message) {
+        let topics = self.topics.entry(container_name.to_owned()).or_default();
+        if message.payload.is_empty() {
+            topics.remove(&message.topic);
+        } else {
+            topics.insert(message.topic.to_owned());
+        }
+    }
+
+    pub fn clear(&mut self, container_name: &str) -> Vec<Message> {
+        self.topics
+            .remove(container_name)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|topic| Message {
+                topic,
+                payload: "".to_owned(),
+            })
+            .collect()
+    }
+
+    pub fn contains(&self, topic: &str) -> bool {
+        self.topics.values().any(|topics| topics.contains(topic))
+    }
+}
+
+#[cfg(test)]
+mod must {
+    use super::{Message, Retained};
+
+    fn create_message(topic: &str, payload: &str) -> Message {
+        Message {
+            topic: topic.to_owned(),
+            payload: payload.to_owned(),
+        }
+    }
+
+    #[test]
+    fn clear_every_tracked_topic_of_container() {
+        // arrange
+        let mut retained = Retained::default();
+        retained.track("borg", &create_message("borg/cpu_usage/state", "1.00"));
+        retained.track("borg", &create_message("borg/logs/state", "started"));
+        retained.track("borg", &create_message("borg/image/state", "borg:1"));
+        retained.track("borg", &create_message("borg/image/state", ""));
+        retained.track("nginx", &create_message("nginx/logs/state", "started"));
+
+        // act
+        let messages = retained.clear("borg");
+
+        // assert
+        let topics: Vec<&str> = messages.iter().map(|m| m.topic.as_str()).collect();
+        assert_eq!(vec!["borg/cpu_usage/state", "borg/logs/state"], topics);
+        assert!(messages.iter().all(|m| m.payload.is_empty()));
+        assert!(!retained.contains("borg/logs/state"));
+        assert!(retained.contains("nginx/logs/state"));
+    }
+}
@@ -0,0 +1,187 @@
+use std::{collections::HashSet, time::Duration};
+
+use crate::configuration::Configuration;
+
+use super::{discovery, retained::Retained, topic};
+
+// retained messages of the subscribed topic trees arrive shortly after connecting, while initial
+// events announce every live device
+pub const SWEEP_DELAY: Duration = Duration::from_secs(30);
+
+pub fn get_subscriptions(conf: &Configuration) -> Vec<String> {
+    let mut subscriptions = vec![topic::tree(conf)];
+    if let Some(filter) = discovery::tree(conf) {
+        subscriptions.push(filter);
+    }
+
+    subscriptions
+}
+
+// topics on the broker, which are neither published by a live device nor known in this session
+pub fn get_stale_topics(
+    candidates: &[String],
+    devices: &HashSet<String>,
+    retained: &Retained,
+    conf: &Configuration,
+) -> Vec<String> {
+    let base = format!("docker2mqtt/{}/", conf.mqtt.client_id);
+
+    // devices of this instance leave their availability in its tree, thus unique ids of instances
+    // with a client id starting the same, e.g. nas_backup next to nas, are kept
+    let known: HashSet<&str> = candidates
+        .iter()
+        .filter_map(|topic| get_device(topic, &base))
+        .collect();
+
+    candidates
+        .iter()
+        .filter(|topic| !retained.contains(topic))
+        .filter(|topic| match get_device(topic, &base) {
+            Some(device) => !devices.contains(device),
+            None => {
+                match get_unique_id(topic).and_then(|id| discovery::parse_unique_id(id, conf)) {
+                    Some((device, _)) => known.contains(device) && !devices.contains(device),
+                    None => false,
+                }
+            }
+        })
+        .cloned()
+        .collect()
+}
+
+fn get_device<'a>(topic: &'a str, base: &str) -> Option<&'a str> {
+    topic.strip_prefix(base)?.split('/').next()
+}
+
+// discovery topics end with docker2mqtt/<unique_id>/config
+fn get_unique_id(topic: &str) -> Option<&str> {
+    let mut segments = topic.rsplit('/');
+    match (segments.next(), segments.next(), segments.next()) {
+        (Some("config"), Some(unique_id), Some("docker2mqtt")) => Some(unique_id),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod must {
+    use std::collections::HashSet;
+
+    use crate::mqtt::{message::Message, retained::Retained};
+
+    use super::{get_stale_topics, get_subscriptions};
+
+    fn create_configuration() -> super::Configuration {
+        serde_yaml::from_str(
+            "
+hassio:
+  discovery: true
+
+mqtt:
+  client_id: qwert
+  host: yuio
+  port: 1234",
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn subscribe_to_own_topic_trees() {
+        // arrange
+        let conf = create_configuration();
+
+        // act
+        let subscriptions = get_subscriptions(&conf);
+
+        // assert
+        assert_eq!(
+            vec![
+                "docker2mqtt/qwert/#",
+                "homeassistant/+/docker2mqtt/+/config"
+            ],
+            subscriptions
+        );
+    }
+
+    #[test]
+    fn return_topics_without_live_device() {
+        // arrange
+        let conf = create_configuration();
+
+        let devices: HashSet<String> = vec!["borg".to_owned()].into_iter().collect();
+
+        let mut retained = Retained::default();
+        retained.track(
+            "borg",
+            &Message {
+                topic: "homeassistant/sensor/docker2mqtt/docker_qwert_borg_image/config".to_owned(),
+                payload: "{}".to_owned(),
+            },
+        );
+
+        let candidates: Vec<String> = vec![
+            "docker2mqtt/qwert/borg/cpu_usage/state",
+            "docker2mqtt/qwert/gone/cpu_usage/state",
+            "docker2mqtt/other/gone/cpu_usage/state",
+            "homeassistant/sensor/docker2mqtt/docker_qwert_borg_image/config",
+            "homeassistant/sensor/docker2mqtt/docker_qwert_gone_image/config",
+            "homeassistant/sensor/docker2mqtt/docker_other_gone_image/config",
+        ]
+        .into_iter()
+        .map(String::from)
+        .collect();
+
+        // act
+        let stale = get_stale_topics(&candidates, &devices, &retained, &conf);
+
+        // assert
+        assert_eq!(
+            vec![
+                "docker2mqtt/qwert/gone/cpu_usage/state",
+                "homeassistant/sensor/docker2mqtt/docker_qwert_gone_image/config",
+            ],
+            stale
+        );
+    }
+
+    #[test]
+    fn keep_topics_of_instance_with_longer_client_id() {
+        // arrange
+        let conf: super::Configuration = serde_yaml::from_str(
+            "
+hassio:
+  discovery: true
+
+mqtt:
+  client_id: nas
+  host: yuio
+  port: 1234",
+        )
+        .unwrap();
+
+        let devices: HashSet<String> = vec!["borg".to_owned()].into_iter().collect();
+
+        let candidates: Vec<String> = vec![
+            "docker2mqtt/nas/gone/lwt",
+            "docker2mqtt/nas_backup/borg/lwt",
+            "homeassistant/sensor/docker2mqtt/docker_nas_gone_task_state/config",
+            "homeassistant/sensor/docker2mqtt/docker_nas_backup_borg_cpu_usage/config",
+            "homeassistant/sensor/docker2mqtt/docker_nas_backup_cpu_usage/config",
+            "homeassistant/sensor/docker2mqtt/docker_nas_backup_gone_image/config",
+        ]
+        .into_iter()
+        .map(String::from)
+        .collect();
+
+        // act
+        let stale = get_stale_topics(&candidates, &devices, &Retained::default(), &conf);
+
+        // assert
+        assert_eq!(
+            vec![
+                "docker2mqtt/nas/gone/lwt",
+                "homeassistant/sensor/docker2mqtt/docker_nas_gone_task_state/config",
+            ],
+            stale
+        );
+    }
+}
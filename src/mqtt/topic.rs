@@ -16,6 +16,27 @@ pub fn state(container_name: &str, event_name: &str, conf: &Configuration) -> St
     )
 }
 
+#[instrument(level = "debug")]
+pub fn attributes(container_name: &str, event_name: &str, conf: &Configuration) -> String {
+    format!(
+        "{}/{}/attributes",
+        base(&conf.mqtt.client_id, container_name),
+        event_name
+    )
+}
+
+pub fn query(conf: &Configuration) -> String {
+    format!("docker2mqtt/{}/query", conf.mqtt.client_id)
+}
+
+pub fn query_reply(conf: &Configuration) -> String {
+    format!("docker2mqtt/{}/query/reply", conf.mqtt.client_id)
+}
+
+pub fn tree(conf: &Configuration) -> String {
+    format!("docker2mqtt/{}/#", conf.mqtt.client_id)
+}
+
 fn device_availability(client_id: &str, container_name: &str) -> String {
     format!("{}/lwt", base(client_id, container_name))
 }
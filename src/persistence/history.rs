@@ -0,0 +1,334 @@
+use std::{
+    collections::VecDeque,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use serde::{Deserialize, Serialize};
+use tokio::{task, time};
+use tracing::debug;
+
+use crate::{
+    bus::Subscriber,
+    configuration::Configuration,
+    events::{self, ContainerEvent, Event, EventType},
+    health,
+};
+
+use super::{Entity, Repository, SharedRepository};
+
+const EXPIRE_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct History {
+    pub container_name: String,
+
+    #[serde(default)]
+    pub entries: VecDeque<Entry>,
+}
+
+impl Entity for History {
+    const COLLECTION: &'static str = "history";
+    const VERSION: u32 = 1;
+
+    fn key(&self) -> String {
+        self.container_name.to_owned()
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct Entry {
+    pub ts: u64,
+    pub event: String,
+    pub value: String,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub severity: Option<String>,
+}
+
+#[derive(Debug, Deserialize, PartialEq)]
+pub struct Query {
+    pub container: String,
+
+    #[serde(default)]
+    pub event: Option<String>,
+
+    #[serde(default)]
+    pub limit: Option<usize>,
+
+    #[serde(default)]
+    pub reply_to: Option<String>,
+}
+
+pub async fn task(mut subscriber: Subscriber, repo: SharedRepository, conf: &Configuration) {
+    let (limit, keep_removed) = match &conf.history {
+        Some(history) => (history.limit, history.keep_removed),
+        None => return,
+    };
+
+    task::spawn(async move {
+        let _alive = health::alive("history");
+        let mut interval = time::interval(EXPIRE_INTERVAL);
+
+        loop {
+            tokio::select! {
+                event = subscriber.recv() => {
+                    let event = match event {
+                        Some(event) => event,
+                        None => break,
+                    };
+
+                    let mut repo = repo.lock().unwrap();
+                    record(event, repo.as_mut(), limit, get_timestamp());
+                }
+                _ = interval.tick() => {
+                    let mut repo = repo.lock().unwrap();
+                    expire(repo.as_mut(), keep_removed, get_timestamp());
+                }
+            }
+        }
+    });
+}
+
+// history survives the removal of a container, thus recreated containers keep their timeline
+fn record(event: Event, repo: &mut dyn Repository, limit: usize, ts: u64) {
+    if !events::is_container(&event.container_name) {
+        return;
+    }
+
+    let entry = match get_entry(&event.event, ts) {
+        Some(entry) => entry,
+        None => return,
+    };
+
+    let mut history = super::find::<History>(repo, &event.container_name).unwrap_or(History {
+        container_name: event.container_name,
+        entries: VecDeque::new(),
+    });
+
+    history.entries.push_back(entry);
+    while history.entries.len() > limit {
+        history.entries.pop_front();
+    }
+
+    super::store(repo, &history);
+}
+
+// removes histories of containers which were destroyed longer than keep_removed seconds ago
+fn expire(repo: &mut dyn Repository, keep_removed: u64, now: u64) {
+    let expired: Vec<String> = super::load::<History>(repo)
+        .into_iter()
+        .filter(|history| match history.entries.back() {
+            Some(entry) => is_destroy(entry) && entry.ts.saturating_add(keep_removed) < now,
+            None => true,
+        })
+        .map(|history| history.container_name)
+        .collect();
+
+    for container_name in expired {
+        debug!("removing history of {}", container_name);
+        super::remove::<History>(repo, &container_name);
+    }
+}
+
+fn is_destroy(entry: &Entry) -> bool {
+    entry.event == "state" && entry.value == "destroy"
+}
+
+fn get_entry(event: &EventType, ts: u64) -> Option<Entry> {
+    let entry = Entry {
+        ts,
+        event: event.to_string(),
+        value: String::new(),
+        message: None,
+        severity: None,
+    };
+
+    match event {
+        EventType::ExitCode(code) => Some(Entry {
+            value: code.to_string(),
+            ..entry
+        }),
+        EventType::LogAlert(alert) => Some(Entry {
+            value: alert.name.to_owned(),
+            message: Some(alert.message.to_owned()),
+            severity: Some(alert.severity.to_owned()),
+            ..entry
+        }),
+        EventType::State(ContainerEvent::Undefined) => None,
+        EventType::State(state) => Some(Entry {
+            value: serde_json::to_value(state)
+                .ok()?
+                .as_str()
+                .unwrap_or_default()
+                .to_owned(),
+            ..entry
+        }),
+        _ => None,
+    }
+}
+
+// returns the latest entries, oldest first
+pub fn query(repo: &dyn Repository, query: &Query) -> Vec<Entry> {
+    let entries: Vec<Entry> = super::find::<History>(repo, &query.container)
+        .map(|history| history.entries)
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|entry| {
+            query
+                .event
+                .as_ref()
+                .is_none_or(|event| *event == entry.event)
+        })
+        .collect();
+
+    let skip = query
+        .limit
+        .map(|limit| entries.len().saturating_sub(limit))
+        .unwrap_or_default();
+
+    entries.into_iter().skip(skip).collect()
+}
+
+fn get_timestamp() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod must {
+    use crate::{
+        events::{Alert, ContainerEvent, Event, EventType},
+        persistence::{memory_repository::MemoryRepository, Repository},
+    };
+
+    use super::{expire, query, record, Query};
+
+    fn create_event(event: EventType) -> Event {
+        Event {
+            container_name: "borg".to_owned(),
+            event,
+        }
+    }
+
+    fn create_query(event: Option<&str>, limit: Option<usize>) -> Query {
+        Query {
+            container: "borg".to_owned(),
+            event: event.map(String::from),
+            limit,
+            reply_to: None,
+        }
+    }
+
+    #[test]
+    fn keep_latest_entries_up_to_limit() {
+        // arrange
+        let mut repo = MemoryRepository::default();
+
+        // act
+        for (ts, state) in [
+            ContainerEvent::Start,
+            ContainerEvent::Die,
+            ContainerEvent::Restart,
+        ]
+        .iter()
+        .enumerate()
+        {
+            record(
+                create_event(EventType::State(state.clone())),
+                &mut repo,
+                2,
+                ts as u64,
+            );
+        }
+
+        // assert
+        let entries = query(&repo, &create_query(None, None));
+        let values: Vec<(u64, &str)> = entries.iter().map(|e| (e.ts, e.value.as_str())).collect();
+        assert_eq!(vec![(1, "die"), (2, "restart")], values);
+    }
+
+    #[test]
+    fn record_exits_and_alerts_only() {
+        // arrange
+        let mut repo = MemoryRepository::default();
+        let alert = Alert {
+            name: "database_refused".to_owned(),
+            severity: "critical".to_owned(),
+            message: "connection refused".to_owned(),
+            timestamp: None,
+        };
+
+        // act
+        record(create_event(EventType::ExitCode(137)), &mut repo, 10, 1);
+        record(create_event(EventType::CpuUsage(5.0)), &mut repo, 10, 2);
+        record(create_event(EventType::LogAlert(alert)), &mut repo, 10, 3);
+
+        // assert
+        let entries = query(&repo, &create_query(None, None));
+        assert_eq!(2, entries.len());
+        assert_eq!("exit_code", entries[0].event);
+        assert_eq!("137", entries[0].value);
+        assert_eq!("log_alert", entries[1].event);
+        assert_eq!(Some("critical".to_owned()), entries[1].severity);
+    }
+
+    #[test]
+    fn filter_query_by_event_and_limit() {
+        // arrange
+        let mut repo = MemoryRepository::default();
+        record(
+            create_event(EventType::State(ContainerEvent::Start)),
+            &mut repo,
+            10,
+            1,
+        );
+        record(create_event(EventType::ExitCode(1)), &mut repo, 10, 2);
+        record(
+            create_event(EventType::State(ContainerEvent::Die)),
+            &mut repo,
+            10,
+            3,
+        );
+
+        // act
+        let entries = query(&repo, &create_query(Some("state"), Some(1)));
+
+        // assert
+        assert_eq!(1, entries.len());
+        assert_eq!("die", entries[0].value);
+    }
+
+    #[test]
+    fn expire_histories_of_removed_containers() {
+        // arrange
+        let mut repo = MemoryRepository::default();
+        for (container_name, state, ts) in [
+            ("borg", ContainerEvent::Destroy, 10),
+            ("nginx", ContainerEvent::Destroy, 50),
+            ("mosquitto", ContainerEvent::Die, 10),
+        ] {
+            let event = Event {
+                container_name: container_name.to_owned(),
+                event: EventType::State(state),
+            };
+            record(event, &mut repo, 10, ts);
+        }
+
+        // act
+        expire(&mut repo, 30, 60);
+
+        // assert
+        let mut containers: Vec<String> = repo
+            .list("history")
+            .into_iter()
+            .map(|record| record.key)
+            .collect();
+        containers.sort();
+        assert_eq!(vec!["mosquitto", "nginx"], containers);
+    }
+}
@@ -0,0 +1,114 @@
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tracing::error;
+
+use crate::persistence::{PersistenceErr, Record, Repository};
+
+const FILE: &str = "docker.json";
+
+// the whole file gets rewritten on every change, thus it stays readable and editable by hand
+pub struct JsonRepository {
+    collections: BTreeMap<String, BTreeMap<String, Entry>>,
+    path: PathBuf,
+}
+
+#[derive(Clone, Deserialize, Serialize)]
+struct Entry {
+    version: u32,
+    value: Value,
+}
+
+pub fn open(directory: &Path) -> Result<JsonRepository, PersistenceErr> {
+    let path = directory.join(FILE);
+    let collections = match fs::read_to_string(&path) {
+        Ok(content) => {
+            serde_json::from_str(&content).map_err(|e| PersistenceErr::Json(path.to_owned(), e))?
+        }
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => BTreeMap::new(),
+        Err(e) => return Err(PersistenceErr::Io(path, e)),
+    };
+
+    Ok(JsonRepository { collections, path })
+}
+
+impl JsonRepository {
+    fn write(&self) {
+        let content = match serde_json::to_string_pretty(&self.collections) {
+            Ok(content) => content,
+            Err(e) => {
+                error!("error serializing json repository: {}", e);
+                return;
+            }
+        };
+
+        // written next to the file and renamed, thus a crash never leaves half a file behind
+        let temporary = self.path.with_extension("json.tmp");
+        let result =
+            fs::write(&temporary, content).and_then(|_| fs::rename(&temporary, &self.path));
+        if let Err(e) = result {
+            error!("error writing {}: {}", self.path.display(), e);
+        }
+    }
+}
+
+impl Repository for JsonRepository {
+    fn collections(&self) -> Vec<String> {
+        self.collections.keys().cloned().collect()
+    }
+
+    fn get(&self, collection: &str, key: &str) -> Option<Record> {
+        let entry = self.collections.get(collection)?.get(key)?;
+
+        Some(to_record(key, entry))
+    }
+
+    fn list(&self, collection: &str) -> Vec<Record> {
+        match self.collections.get(collection) {
+            Some(entries) => entries
+                .iter()
+                .map(|(key, entry)| to_record(key, entry))
+                .collect(),
+            None => Vec::new(),
+        }
+    }
+
+    fn put(&mut self, collection: &str, record: Record) {
+        self.collections
+            .entry(collection.to_owned())
+            .or_default()
+            .insert(
+                record.key,
+                Entry {
+                    version: record.version,
+                    value: record.value,
+                },
+            );
+
+        self.write();
+    }
+
+    fn delete(&mut self, collection: &str, key: &str) {
+        let removed = self
+            .collections
+            .get_mut(collection)
+            .and_then(|entries| entries.remove(key));
+
+        if removed.is_some() {
+            self.write();
+        }
+    }
+}
+
+fn to_record(key: &str, entry: &Entry) -> Record {
+    Record {
+        key: key.to_owned(),
+        version: entry.version,
+        value: entry.value.clone(),
+    }
+}
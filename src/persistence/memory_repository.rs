@@ -0,0 +1,38 @@
+use std::collections::BTreeMap;
+
+use crate::persistence::{Record, Repository};
+
+#[derive(Default)]
+pub struct MemoryRepository {
+    collections: BTreeMap<String, BTreeMap<String, Record>>,
+}
+
+impl Repository for MemoryRepository {
+    fn collections(&self) -> Vec<String> {
+        self.collections.keys().cloned().collect()
+    }
+
+    fn get(&self, collection: &str, key: &str) -> Option<Record> {
+        self.collections.get(collection)?.get(key).cloned()
+    }
+
+    fn list(&self, collection: &str) -> Vec<Record> {
+        self.collections
+            .get(collection)
+            .map(|records| records.values().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    fn put(&mut self, collection: &str, record: Record) {
+        self.collections
+            .entry(collection.to_owned())
+            .or_default()
+            .insert(record.key.to_owned(), record);
+    }
+
+    fn delete(&mut self, collection: &str, key: &str) {
+        if let Some(records) = self.collections.get_mut(collection) {
+            records.remove(key);
+        }
+    }
+}
@@ -1,40 +1,189 @@
-use tokio::{
-    sync::{
-        broadcast::{self, error::RecvError},
-        oneshot,
-    },
-    task,
+use std::{
+    fmt, io,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
 };
+
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::Value;
+use tokio::{sync::oneshot, task};
 use tracing::{debug, error};
 
 use self::no_persistence_repository::NoPersistenceRepository;
-use crate::configuration::Configuration;
-use crate::events::{ContainerEvent, Event, EventType};
+use crate::configuration::{Backend, Configuration};
+
+pub use self::history::{query as query_history, task as history_task, Query};
+pub use self::snapshot::{task as state_task, Snapshot};
 
+mod history;
+mod json_repository;
+#[cfg(test)]
+pub mod memory_repository;
 mod no_persistence_repository;
 mod sled_repository;
+mod snapshot;
+mod sqlite_repository;
+
+pub type SharedRepository = Arc<Mutex<Box<dyn Repository>>>;
 
 pub trait Repository: Send {
-    fn list(&self) -> Vec<String>;
-    fn add(&mut self, container_name: String);
-    fn delete(&mut self, container_name: String);
+    fn collections(&self) -> Vec<String>;
+    fn get(&self, collection: &str, key: &str) -> Option<Record>;
+    fn list(&self, collection: &str) -> Vec<Record>;
+    fn put(&mut self, collection: &str, record: Record);
+    fn delete(&mut self, collection: &str, key: &str);
+    fn flush(&mut self) {}
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct Record {
+    pub key: String,
+    pub version: u32,
+    pub value: Value,
+}
+
+// typed records, the version allows to convert values written by earlier releases
+pub trait Entity: Sized + Serialize + DeserializeOwned {
+    const COLLECTION: &'static str;
+    const VERSION: u32;
+
+    fn key(&self) -> String;
+
+    fn upgrade(_version: u32, value: Value) -> Option<Self> {
+        serde_json::from_value(value).ok()
+    }
 }
 
-pub fn create_repository(conf: &Configuration) -> Box<dyn Repository> {
-    match &conf.docker.stream_logs {
-        true => {
-            debug!("Creating sled repository");
-            Box::new(sled_repository::create("/docker2mqtt/data".to_owned()))
+#[derive(Debug)]
+pub enum PersistenceErr {
+    Io(PathBuf, io::Error),
+    Json(PathBuf, serde_json::Error),
+    Same(Backend),
+    Sled(sled::Error),
+    Sqlite(rusqlite::Error),
+}
+
+impl fmt::Display for PersistenceErr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PersistenceErr::Io(path, e) => write!(f, "could not access {}: {}", path.display(), e),
+            PersistenceErr::Json(path, e) => write!(f, "invalid {}: {}", path.display(), e),
+            PersistenceErr::Same(backend) => {
+                write!(f, "source and target backend are both {}", backend)
+            }
+            PersistenceErr::Sled(e) => write!(f, "could not open sled database: {}", e),
+            PersistenceErr::Sqlite(e) => write!(f, "could not open sqlite database: {}", e),
         }
+    }
+}
+
+pub fn create_repository(
+    conf: &Configuration,
+    data_dir: &Path,
+) -> Result<SharedRepository, PersistenceErr> {
+    let repo = match &conf.docker.persist_state {
+        true => open(conf.docker.persist_backend, data_dir)?,
         false => {
             debug!("Creating no persistence repository");
             Box::new(NoPersistenceRepository {})
         }
+    };
+
+    Ok(Arc::new(Mutex::new(repo)))
+}
+
+pub fn open(backend: Backend, data_dir: &Path) -> Result<Box<dyn Repository>, PersistenceErr> {
+    debug!("Creating {} repository", backend);
+
+    Ok(match backend {
+        Backend::Json => Box::new(json_repository::open(data_dir)?),
+        Backend::Sled => Box::new(sled_repository::open(data_dir)?),
+        Backend::Sqlite => Box::new(sqlite_repository::open(data_dir)?),
+    })
+}
+
+// copies every record and returns the count of copied records
+pub fn migrate(from: Backend, to: Backend, data_dir: &Path) -> Result<usize, PersistenceErr> {
+    if from == to {
+        return Err(PersistenceErr::Same(from));
+    }
+
+    let source = open(from, data_dir)?;
+    let mut target = open(to, data_dir)?;
+
+    Ok(copy(source.as_ref(), target.as_mut()))
+}
+
+fn copy(source: &dyn Repository, target: &mut dyn Repository) -> usize {
+    let mut count = 0;
+    for collection in source.collections() {
+        for record in source.list(&collection) {
+            target.put(&collection, record);
+            count += 1;
+        }
     }
+
+    target.flush();
+    count
+}
+
+pub fn load<T: Entity>(repo: &dyn Repository) -> Vec<T> {
+    repo.list(T::COLLECTION)
+        .into_iter()
+        .filter_map(to_entity)
+        .collect()
+}
+
+pub fn find<T: Entity>(repo: &dyn Repository, key: &str) -> Option<T> {
+    repo.get(T::COLLECTION, key).and_then(to_entity)
+}
+
+pub fn store<T: Entity>(repo: &mut dyn Repository, entity: &T) {
+    let value = match serde_json::to_value(entity) {
+        Ok(value) => value,
+        Err(e) => {
+            error!("error serializing {}: {}", T::COLLECTION, e);
+            return;
+        }
+    };
+
+    repo.put(
+        T::COLLECTION,
+        Record {
+            key: entity.key(),
+            version: T::VERSION,
+            value,
+        },
+    );
+}
+
+pub fn remove<T: Entity>(repo: &mut dyn Repository, key: &str) {
+    repo.delete(T::COLLECTION, key);
 }
 
-pub async fn init_task(init_sender: oneshot::Sender<Vec<String>>, repo: &dyn Repository) {
-    let list = repo.list();
+fn to_entity<T: Entity>(record: Record) -> Option<T> {
+    if record.version != T::VERSION {
+        return T::upgrade(record.version, record.value);
+    }
+
+    match serde_json::from_value(record.value) {
+        Ok(entity) => Some(entity),
+        Err(e) => {
+            error!("error reading {} {}: {}", T::COLLECTION, record.key, e);
+            None
+        }
+    }
+}
+
+// flushes pending writes and releases the database, tasks writing afterwards are ignored
+pub fn close(repo: &SharedRepository) {
+    let mut repo = repo.lock().unwrap();
+    repo.flush();
+    *repo = Box::new(NoPersistenceRepository {});
+}
+
+pub async fn init_task(init_sender: oneshot::Sender<Vec<Snapshot>>, repo: &SharedRepository) {
+    let list = load(repo.lock().unwrap().as_ref());
     task::spawn(async move {
         if let Err(err) = init_sender.send(list) {
             error!("error sending initial vector: {:?}", err);
@@ -42,27 +191,120 @@ pub async fn init_task(init_sender: oneshot::Sender<Vec<String>>, repo: &dyn Rep
     });
 }
 
-pub async fn state_task(mut receiver: broadcast::Receiver<Event>, mut repo: Box<dyn Repository>) {
-    task::spawn(async move {
-        loop {
-            match receiver.recv().await {
-                Ok(event) => dispatch_event(event, &mut repo),
-                Err(RecvError::Closed) => break,
-                Err(e) => {
-                    error!("receive failed: {}", e);
-                    continue;
-                }
-            }
+#[cfg(test)]
+mod must {
+    use std::{
+        path::PathBuf,
+        sync::{Arc, Mutex},
+    };
+
+    use serde_json::json;
+
+    use crate::configuration::Backend;
+
+    use super::{close, copy, open, Record, Repository, SharedRepository};
+
+    fn create_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("d2m_persistence_{}", name));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+
+        dir
+    }
+
+    fn create_record(key: &str, version: u32) -> Record {
+        Record {
+            key: key.to_owned(),
+            version,
+            value: json!({ "key": key }),
         }
-    });
-}
+    }
+
+    fn assert_backend(repo: &mut dyn Repository) {
+        // act
+        repo.put("containers", create_record("borg", 1));
+        repo.put("containers", create_record("nginx", 1));
+        repo.put("containers", create_record("borg", 2));
+        repo.put("history", create_record("borg", 1));
+        repo.delete("containers", "nginx");
+
+        // assert
+        assert_eq!(
+            vec!["containers".to_owned(), "history".to_owned()],
+            repo.collections()
+        );
+        assert_eq!(vec![create_record("borg", 2)], repo.list("containers"));
+        assert_eq!(vec![create_record("borg", 1)], repo.list("history"));
+        assert_eq!(Some(create_record("borg", 1)), repo.get("history", "borg"));
+        assert_eq!(None, repo.get("history", "nginx"));
+    }
+
+    #[test]
+    fn store_records_in_every_backend() {
+        for backend in [Backend::Json, Backend::Sled, Backend::Sqlite] {
+            // arrange
+            let dir = create_dir(&backend.to_string());
+            let mut repo = open(backend, &dir).unwrap();
+
+            // act && assert
+            assert_backend(repo.as_mut());
+        }
+    }
+
+    #[test]
+    fn keep_records_after_reopen() {
+        for backend in [Backend::Json, Backend::Sled, Backend::Sqlite] {
+            // arrange
+            let dir = create_dir(&format!("reopen_{}", backend));
+            let mut repo = open(backend, &dir).unwrap();
+            repo.put("containers", create_record("borg", 1));
+            repo.flush();
+            drop(repo);
+
+            // act
+            let repo = open(backend, &dir).unwrap();
 
-fn dispatch_event(event: Event, repo: &mut Box<dyn Repository>) {
-    if let EventType::State(container_event) = event.event {
-        match container_event {
-            ContainerEvent::Create => repo.add(event.container_name),
-            ContainerEvent::Destroy => repo.delete(event.container_name),
-            _ => {}
+            // assert
+            assert_eq!(vec![create_record("borg", 1)], repo.list("containers"));
         }
     }
+
+    #[test]
+    fn copy_every_record_between_backends() {
+        // arrange
+        let dir = create_dir("copy");
+        let mut source = open(Backend::Sled, &dir).unwrap();
+        source.put("containers", create_record("borg", 1));
+        source.put("history", create_record("nginx", 1));
+
+        let mut target = open(Backend::Sqlite, &dir).unwrap();
+
+        // act
+        let count = copy(source.as_ref(), target.as_mut());
+
+        // assert
+        assert_eq!(2, count);
+        assert_eq!(source.collections(), target.collections());
+        assert_eq!(vec![create_record("nginx", 1)], target.list("history"));
+    }
+
+    #[test]
+    fn keep_flushed_records_after_close() {
+        // arrange
+        let dir = create_dir("close");
+        let repo: SharedRepository = Arc::new(Mutex::new(open(Backend::Sled, &dir).unwrap()));
+        repo.lock()
+            .unwrap()
+            .put("containers", create_record("borg", 1));
+
+        // act
+        close(&repo);
+        repo.lock()
+            .unwrap()
+            .put("containers", create_record("nginx", 1));
+
+        // assert
+        let reopened = open(Backend::Sled, &dir).unwrap();
+        assert_eq!(vec![create_record("borg", 1)], reopened.list("containers"));
+    }
 }
@@ -1,13 +1,21 @@
-use crate::persistence::Repository;
+use crate::persistence::{Record, Repository};
 
 pub struct NoPersistenceRepository {}
 
 impl Repository for NoPersistenceRepository {
-    fn list(&self) -> Vec<String> {
+    fn collections(&self) -> Vec<String> {
         Vec::new()
     }
 
-    fn add(&mut self, _: String) {}
+    fn get(&self, _: &str, _: &str) -> Option<Record> {
+        None
+    }
+
+    fn list(&self, _: &str) -> Vec<Record> {
+        Vec::new()
+    }
+
+    fn put(&mut self, _: &str, _: Record) {}
 
-    fn delete(&mut self, _: String) {}
+    fn delete(&mut self, _: &str, _: &str) {}
 }
@@ -1,25 +1,124 @@
-use sled::Db;
-use std::{ops::Add, str};
-use tracing::error;
+use std::path::Path;
 
-use crate::persistence::Repository;
+use serde_json::Value;
+use sled::{Db, Tree};
+use tracing::{error, info};
+
+use crate::persistence::{PersistenceErr, Record, Repository};
+
+use super::snapshot::Snapshot;
+use super::Entity;
+
+const DIRECTORY: &str = "docker.db";
 
 pub struct SledRepository {
     database: Db,
 }
 
-pub fn create(directory: String) -> SledRepository {
-    SledRepository {
-        database: sled::open(directory.add("/docker.db")).unwrap(), //TODO Panic okay?
+pub fn open(directory: &Path) -> Result<SledRepository, PersistenceErr> {
+    let path = directory.join(DIRECTORY);
+    let misplaced = !path.exists() && is_database(directory);
+
+    let database = sled::open(path).map_err(PersistenceErr::Sled)?;
+    if misplaced {
+        import_misplaced(directory, &database).map_err(PersistenceErr::Sled)?;
+    }
+
+    migrate_default_tree(&database).map_err(PersistenceErr::Sled)?;
+
+    Ok(SledRepository { database })
+}
+
+fn is_database(directory: &Path) -> bool {
+    directory.join("conf").is_file() && directory.join("db").is_file()
+}
+
+// a database opened directly in the data directory gets copied once, the files are left in place
+fn import_misplaced(directory: &Path, database: &Db) -> sled::Result<()> {
+    info!(
+        "importing sled database from {} into {}",
+        directory.display(),
+        DIRECTORY
+    );
+
+    let misplaced = sled::open(directory)?;
+    database.import(misplaced.export());
+    database.flush()?;
+
+    Ok(())
+}
+
+// earlier versions stored plain container names in the default tree
+fn migrate_default_tree(database: &Db) -> sled::Result<()> {
+    let containers = database.open_tree(Snapshot::COLLECTION)?;
+    for entry in database.iter() {
+        let (key, value) = entry?;
+        let value = serde_json::from_slice(&value)
+            .unwrap_or_else(|_| Value::String(String::from_utf8_lossy(&value).into_owned()));
+
+        let record = Record {
+            key: String::from_utf8_lossy(&key).into_owned(),
+            version: 0,
+            value,
+        };
+
+        if let Ok(bytes) = serde_json::to_vec(&record) {
+            containers.insert(&key, bytes)?;
+            database.remove(&key)?;
+        }
+    }
+
+    Ok(())
+}
+
+impl SledRepository {
+    fn tree(&self, collection: &str) -> Option<Tree> {
+        match self.database.open_tree(collection) {
+            Ok(tree) => Some(tree),
+            Err(e) => {
+                error!("error opening collection {}: {}", collection, e);
+                None
+            }
+        }
     }
 }
 
 impl Repository for SledRepository {
-    fn list(&self) -> Vec<String> {
+    fn collections(&self) -> Vec<String> {
+        let default = self.database.name();
+        let mut collections: Vec<String> = self
+            .database
+            .tree_names()
+            .into_iter()
+            .filter(|name| *name != default)
+            .map(|name| String::from_utf8_lossy(&name).into_owned())
+            .filter(|name| !self.list(name).is_empty())
+            .collect();
+
+        collections.sort();
+        collections
+    }
+
+    fn get(&self, collection: &str, key: &str) -> Option<Record> {
+        match self.tree(collection)?.get(key.as_bytes()) {
+            Ok(value) => value.and_then(|value| convert_to_record(&value)),
+            Err(err) => {
+                error!("error receiving entry from repository: {}", err);
+                None
+            }
+        }
+    }
+
+    fn list(&self, collection: &str) -> Vec<Record> {
+        let tree = match self.tree(collection) {
+            Some(tree) => tree,
+            None => return Vec::new(),
+        };
+
         let mut entries = Vec::new();
-        for entry_result in self.database.iter() {
+        for entry_result in tree.iter() {
             match entry_result {
-                Ok(entry) => convert_to_string(entry.1.as_ref())
+                Ok(entry) => convert_to_record(entry.1.as_ref())
                     .into_iter()
                     .for_each(|v| entries.push(v)),
                 Err(err) => error!("error receiving entry from repository: {}", err),
@@ -28,29 +127,97 @@ impl Repository for SledRepository {
         entries
     }
 
-    fn add(&mut self, container_name: String) {
-        let result = self
-            .database
-            .insert(container_name.as_bytes(), container_name.as_bytes());
-        if let Err(e) = result {
-            error!("error saving string: {}", e)
+    fn put(&mut self, collection: &str, record: Record) {
+        let tree = match self.tree(collection) {
+            Some(tree) => tree,
+            None => return,
+        };
+
+        let value = match serde_json::to_vec(&record) {
+            Ok(value) => value,
+            Err(e) => {
+                error!("error serializing entry: {}", e);
+                return;
+            }
+        };
+
+        if let Err(e) = tree.insert(record.key.as_bytes(), value) {
+            error!("error saving entry: {}", e)
+        }
+    }
+
+    fn delete(&mut self, collection: &str, key: &str) {
+        let tree = match self.tree(collection) {
+            Some(tree) => tree,
+            None => return,
+        };
+
+        if let Err(e) = tree.remove(key.as_bytes()) {
+            error!("error deleting entry: {}", e)
         }
     }
 
-    fn delete(&mut self, container_name: String) {
-        let result = self.database.remove(container_name.as_bytes());
-        if let Err(e) = result {
-            error!("error deleting string: {}", e)
+    fn flush(&mut self) {
+        if let Err(e) = self.database.flush() {
+            error!("error flushing repository: {}", e)
         }
     }
 }
 
-fn convert_to_string(bytes: &[u8]) -> Option<String> {
-    match str::from_utf8(bytes) {
-        Ok(r) => Option::Some(r.to_owned()),
+fn convert_to_record(bytes: &[u8]) -> Option<Record> {
+    match serde_json::from_slice(bytes) {
+        Ok(record) => Some(record),
         Err(err) => {
-            error!("error converting bytes to String: {}", err);
-            Option::None
+            error!("error converting bytes to record: {}", err);
+            None
         }
     }
 }
+
+#[cfg(test)]
+mod must {
+    use super::{open, Repository};
+
+    #[test]
+    fn migrate_plain_names_of_earlier_versions() {
+        // arrange
+        let dir = std::env::temp_dir().join("d2m_sled_legacy");
+        let _ = std::fs::remove_dir_all(&dir);
+        {
+            // earlier releases appended /docker.db to the data directory
+            let database = sled::open(format!("{}/docker.db", dir.display())).unwrap();
+            database.insert("watchtower", "watchtower").unwrap();
+            database.flush().unwrap();
+        }
+
+        // act
+        let repo = open(&dir).unwrap();
+
+        // assert
+        let records = repo.list("containers");
+        assert_eq!(1, records.len());
+        assert_eq!(0, records[0].version);
+        assert_eq!(serde_json::json!("watchtower"), records[0].value);
+    }
+
+    #[test]
+    fn import_database_of_the_data_directory() {
+        // arrange
+        let dir = std::env::temp_dir().join("d2m_sled_misplaced");
+        let _ = std::fs::remove_dir_all(&dir);
+        {
+            let database = sled::open(&dir).unwrap();
+            database.insert("watchtower", "watchtower").unwrap();
+            database.flush().unwrap();
+        }
+
+        // act
+        let repo = open(&dir).unwrap();
+
+        // assert
+        let records = repo.list("containers");
+        assert_eq!(1, records.len());
+        assert_eq!(serde_json::json!("watchtower"), records[0].value);
+        assert!(dir.join(super::DIRECTORY).is_dir());
+    }
+}
@@ -0,0 +1,266 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tokio::task;
+
+use crate::{
+    bus::Subscriber,
+    configuration::Configuration,
+    events::{self, ContainerEvent, Event, EventType},
+    health, mqtt,
+    reload::Reloads,
+};
+
+use super::{Entity, Repository, SharedRepository};
+
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct Snapshot {
+    pub container_name: String,
+
+    #[serde(default)]
+    pub id: Option<String>,
+
+    #[serde(default)]
+    pub image: Option<String>,
+
+    #[serde(default)]
+    pub state: Option<ContainerEvent>,
+
+    // every retained topic published for the container, including discovery
+    #[serde(default, alias = "discovery_topics")]
+    pub topics: Vec<String>,
+}
+
+impl Snapshot {
+    fn new(container_name: &str) -> Snapshot {
+        Snapshot {
+            container_name: container_name.to_owned(),
+            ..Default::default()
+        }
+    }
+
+    // returns true if any topic was not known before
+    fn add_topics(&mut self, topics: Vec<String>) -> bool {
+        let mut added = false;
+        for topic in topics.into_iter() {
+            if !self.topics.contains(&topic) {
+                self.topics.push(topic);
+                added = true;
+            }
+        }
+
+        added
+    }
+}
+
+impl Entity for Snapshot {
+    const COLLECTION: &'static str = "containers";
+    const VERSION: u32 = 1;
+
+    fn key(&self) -> String {
+        self.container_name.to_owned()
+    }
+
+    // version 0 records hold the plain container name
+    fn upgrade(_version: u32, value: Value) -> Option<Self> {
+        match value {
+            Value::String(container_name) => Some(Snapshot::new(&container_name)),
+            value => serde_json::from_value(value).ok(),
+        }
+    }
+}
+
+pub async fn task(mut subscriber: Subscriber, repo: SharedRepository, mut reloads: Reloads) {
+    task::spawn(async move {
+        let _alive = health::alive("persistence");
+
+        let mut conf = reloads.borrow().clone();
+        let mut snapshots: HashMap<String, Snapshot> =
+            super::load::<Snapshot>(repo.lock().unwrap().as_ref())
+                .into_iter()
+                .map(|snapshot| (snapshot.container_name.to_owned(), snapshot))
+                .collect();
+
+        loop {
+            tokio::select! {
+                event = subscriber.recv() => match event {
+                    Some(event) => {
+                        let mut repo = repo.lock().unwrap();
+                        dispatch_event(event, &mut snapshots, repo.as_mut(), &conf);
+                    }
+                    None => break,
+                },
+                Ok(()) = reloads.changed() => {
+                    conf = reloads.borrow().clone();
+                    let mut repo = repo.lock().unwrap();
+                    add_discovery_topics(&mut snapshots, repo.as_mut(), &conf);
+                }
+            }
+        }
+    });
+}
+
+fn dispatch_event(
+    event: Event,
+    snapshots: &mut HashMap<String, Snapshot>,
+    repo: &mut dyn Repository,
+    conf: &Configuration,
+) {
+    if !events::is_container(&event.container_name) {
+        return;
+    }
+
+    let container_name = event.container_name.to_owned();
+    if let EventType::State(ContainerEvent::Destroy) = event.event {
+        snapshots.remove(&container_name);
+        super::remove::<Snapshot>(repo, &container_name);
+        return;
+    }
+
+    let snapshot = snapshots
+        .entry(container_name.to_owned())
+        .or_insert_with(|| Snapshot::new(&container_name));
+
+    let added = snapshot.add_topics(mqtt::get_retained_topics(&event, conf));
+    match event.event {
+        EventType::ContainerId(id) => snapshot.id = Some(id),
+        EventType::Image(image) => snapshot.image = Some(image),
+        EventType::State(state) => snapshot.state = Some(state),
+        _ if !added => return,
+        _ => {}
+    }
+
+    super::store(repo, snapshot);
+}
+
+// discovery gets republished on reload, thus topics may change with the new configuration
+fn add_discovery_topics(
+    snapshots: &mut HashMap<String, Snapshot>,
+    repo: &mut dyn Repository,
+    conf: &Configuration,
+) {
+    for snapshot in snapshots.values_mut() {
+        let topics = mqtt::get_discovery_topics(&snapshot.container_name, conf);
+        if snapshot.add_topics(topics) {
+            super::store(repo, snapshot);
+        }
+    }
+}
+
+#[cfg(test)]
+mod must {
+    use std::collections::HashMap;
+
+    use serde_json::json;
+
+    use crate::{
+        events::{ContainerEvent, Event, EventType},
+        persistence::{self, memory_repository::MemoryRepository, Record, Repository},
+    };
+
+    use super::{dispatch_event, Snapshot};
+
+    fn create_configuration() -> super::Configuration {
+        serde_yaml::from_str(
+            "
+hassio:
+  discovery: true
+
+mqtt:
+  client_id: qwert
+  host: yuio
+  port: 1234",
+        )
+        .unwrap()
+    }
+
+    fn create_event(container_name: &str, event: EventType) -> Event {
+        Event {
+            container_name: container_name.to_owned(),
+            event,
+        }
+    }
+
+    #[test]
+    fn store_snapshot_per_container() {
+        // arrange
+        let conf = create_configuration();
+        let mut snapshots = HashMap::new();
+        let mut repo = MemoryRepository::default();
+
+        // act
+        for event in [
+            create_event("borg", EventType::Image("borg:1.2".to_owned())),
+            create_event("borg", EventType::ContainerId("4a1b".to_owned())),
+            create_event("borg", EventType::State(ContainerEvent::Create)),
+            create_event("borg", EventType::State(ContainerEvent::Start)),
+            create_event("borg", EventType::CpuUsage(1.0)),
+            create_event("_host", EventType::State(ContainerEvent::Create)),
+        ] {
+            dispatch_event(event, &mut snapshots, &mut repo, &conf);
+        }
+
+        // assert
+        let stored: Vec<Snapshot> = persistence::load(&repo);
+        assert_eq!(1, stored.len());
+        assert_eq!("borg", stored[0].container_name);
+        assert_eq!(Some("4a1b".to_owned()), stored[0].id);
+        assert_eq!(Some("borg:1.2".to_owned()), stored[0].image);
+        assert_eq!(Some(ContainerEvent::Start), stored[0].state);
+        for topic in [
+            "homeassistant/sensor/docker2mqtt/docker_qwert_borg_cpu_usage/config",
+            "docker2mqtt/qwert/borg/cpu_usage/state",
+            "docker2mqtt/qwert/borg/lwt",
+        ] {
+            assert!(stored[0].topics.contains(&topic.to_owned()));
+        }
+    }
+
+    #[test]
+    fn delete_snapshot_on_destroy() {
+        // arrange
+        let conf = create_configuration();
+        let mut snapshots = HashMap::new();
+        let mut repo = MemoryRepository::default();
+
+        dispatch_event(
+            create_event("borg", EventType::State(ContainerEvent::Create)),
+            &mut snapshots,
+            &mut repo,
+            &conf,
+        );
+
+        // act
+        dispatch_event(
+            create_event("borg", EventType::State(ContainerEvent::Destroy)),
+            &mut snapshots,
+            &mut repo,
+            &conf,
+        );
+
+        // assert
+        assert!(snapshots.is_empty());
+        assert!(repo.list("containers").is_empty());
+    }
+
+    #[test]
+    fn upgrade_plain_container_names() {
+        // arrange
+        let mut repo = MemoryRepository::default();
+        repo.put(
+            "containers",
+            Record {
+                key: "watchtower".to_owned(),
+                version: 0,
+                value: json!("watchtower"),
+            },
+        );
+
+        // act
+        let snapshots: Vec<Snapshot> = persistence::load(&repo);
+
+        // assert
+        assert_eq!(vec![Snapshot::new("watchtower")], snapshots);
+    }
+}
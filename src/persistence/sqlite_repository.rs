@@ -0,0 +1,125 @@
+use std::path::Path;
+
+use rusqlite::{params, Connection, OptionalExtension};
+use serde_json::Value;
+use tracing::error;
+
+use crate::persistence::{PersistenceErr, Record, Repository};
+
+const FILE: &str = "docker.sqlite";
+
+pub struct SqliteRepository {
+    connection: Connection,
+}
+
+pub fn open(directory: &Path) -> Result<SqliteRepository, PersistenceErr> {
+    let connection = Connection::open(directory.join(FILE)).map_err(PersistenceErr::Sqlite)?;
+    connection
+        .execute(
+            "CREATE TABLE IF NOT EXISTS records (
+                collection TEXT NOT NULL,
+                key TEXT NOT NULL,
+                version INTEGER NOT NULL,
+                value TEXT NOT NULL,
+                PRIMARY KEY (collection, key)
+            )",
+            [],
+        )
+        .map_err(PersistenceErr::Sqlite)?;
+
+    Ok(SqliteRepository { connection })
+}
+
+impl Repository for SqliteRepository {
+    fn collections(&self) -> Vec<String> {
+        let result = self
+            .connection
+            .prepare("SELECT DISTINCT collection FROM records ORDER BY collection")
+            .and_then(|mut statement| {
+                statement
+                    .query_map([], |row| row.get(0))?
+                    .collect::<Result<Vec<String>, _>>()
+            });
+
+        result.unwrap_or_else(|e| {
+            error!("error receiving collections from repository: {}", e);
+            Vec::new()
+        })
+    }
+
+    fn get(&self, collection: &str, key: &str) -> Option<Record> {
+        let result = self
+            .connection
+            .query_row(
+                "SELECT key, version, value FROM records WHERE collection = ?1 AND key = ?2",
+                params![collection, key],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+            )
+            .optional();
+
+        match result {
+            Ok(row) => row.and_then(to_record),
+            Err(e) => {
+                error!("error receiving entry from repository: {}", e);
+                None
+            }
+        }
+    }
+
+    fn list(&self, collection: &str) -> Vec<Record> {
+        let result = self
+            .connection
+            .prepare("SELECT key, version, value FROM records WHERE collection = ?1 ORDER BY key")
+            .and_then(|mut statement| {
+                statement
+                    .query_map(params![collection], |row| {
+                        Ok((row.get(0)?, row.get(1)?, row.get(2)?))
+                    })?
+                    .collect::<Result<Vec<(String, u32, String)>, _>>()
+            });
+
+        match result {
+            Ok(rows) => rows.into_iter().filter_map(to_record).collect(),
+            Err(e) => {
+                error!("error receiving entries from repository: {}", e);
+                Vec::new()
+            }
+        }
+    }
+
+    fn put(&mut self, collection: &str, record: Record) {
+        let result = self.connection.execute(
+            "INSERT OR REPLACE INTO records (collection, key, version, value) VALUES (?1, ?2, ?3, ?4)",
+            params![collection, record.key, record.version, record.value.to_string()],
+        );
+
+        if let Err(e) = result {
+            error!("error saving entry: {}", e)
+        }
+    }
+
+    fn delete(&mut self, collection: &str, key: &str) {
+        let result = self.connection.execute(
+            "DELETE FROM records WHERE collection = ?1 AND key = ?2",
+            params![collection, key],
+        );
+
+        if let Err(e) = result {
+            error!("error deleting entry: {}", e)
+        }
+    }
+}
+
+fn to_record((key, version, value): (String, u32, String)) -> Option<Record> {
+    match serde_json::from_str::<Value>(&value) {
+        Ok(value) => Some(Record {
+            key,
+            version,
+            value,
+        }),
+        Err(e) => {
+            error!("error converting entry {}: {}", key, e);
+            None
+        }
+    }
+}
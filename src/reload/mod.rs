@@ -0,0 +1,288 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::{Duration, SystemTime},
+};
+
+use tokio::{
+    signal::unix::{signal, SignalKind},
+    sync::watch,
+    task, time,
+};
+use tracing::{error, info, warn};
+
+use crate::configuration::{Configuration, Docker};
+
+const FILE_POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+pub type Reloads = watch::Receiver<Arc<Configuration>>;
+
+pub fn channel(conf: &Configuration) -> (watch::Sender<Arc<Configuration>>, Reloads) {
+    watch::channel(Arc::new(conf.clone()))
+}
+
+pub async fn task(sender: watch::Sender<Arc<Configuration>>, path: Option<PathBuf>) {
+    let mut hangup = match signal(SignalKind::hangup()) {
+        Ok(hangup) => hangup,
+        Err(e) => {
+            error!(
+                "could not listen for SIGHUP, configuration reload disabled: {}",
+                e
+            );
+            return;
+        }
+    };
+
+    let files = Configuration::files(path.as_deref());
+
+    task::spawn(async move {
+        let mut modified = get_modified(&files);
+        let mut interval = time::interval(FILE_POLL_INTERVAL);
+
+        loop {
+            tokio::select! {
+                _ = hangup.recv() => {
+                    info!("received SIGHUP, reloading configuration");
+                }
+                _ = interval.tick() => {
+                    let current = get_modified(&files);
+                    if current == modified {
+                        continue;
+                    }
+
+                    modified = current;
+                    info!("configuration file changed, reloading configuration");
+                }
+            }
+
+            reload(&sender, path.as_deref());
+        }
+    });
+}
+
+fn reload(sender: &watch::Sender<Arc<Configuration>>, path: Option<&Path>) {
+    let conf = match Configuration::load(path) {
+        Ok(conf) => conf,
+        Err(e) => {
+            error!("keeping current configuration, {}", e);
+            return;
+        }
+    };
+
+    let current = sender.borrow().clone();
+    if *current == conf {
+        info!("configuration unchanged");
+        return;
+    }
+
+    for section in get_restart_required(&current, &conf) {
+        warn!("changes to {} take effect after a restart", section);
+    }
+
+    let conf = keep_restart_required(&current, conf);
+    if *current == conf {
+        info!("no changes to apply until a restart");
+        return;
+    }
+
+    if sender.send(Arc::new(conf)).is_err() {
+        warn!("no task listens for configuration changes");
+        return;
+    }
+
+    info!("configuration reloaded");
+}
+
+// sections which are read once on startup, e.g. to connect to the broker
+fn get_restart_required(current: &Configuration, conf: &Configuration) -> Vec<&'static str> {
+    let mut sections = vec![];
+
+    if current.docker.persist_backend != conf.docker.persist_backend {
+        sections.push("docker.persist_backend");
+    }
+
+    if current.docker.persist_state != conf.docker.persist_state {
+        sections.push("docker.persist_state");
+    }
+
+    if current.docker.track_networks != conf.docker.track_networks {
+        sections.push("docker.track_networks");
+    }
+
+    if current.docker.track_uptime != conf.docker.track_uptime {
+        sections.push("docker.track_uptime");
+    }
+
+    if current.docker.track_volumes != conf.docker.track_volumes {
+        sections.push("docker.track_volumes");
+    }
+
+    if current.history != conf.history {
+        sections.push("history");
+    }
+
+    if current.host != conf.host {
+        sections.push("host");
+    }
+
+    if current.http != conf.http {
+        sections.push("http");
+    }
+
+    if current.logging != conf.logging {
+        sections.push("logging");
+    }
+
+    if current.mqtt != conf.mqtt {
+        sections.push("mqtt");
+    }
+
+    if current.swarm != conf.swarm {
+        sections.push("swarm");
+    }
+
+    if current.updates != conf.updates {
+        sections.push("updates");
+    }
+
+    sections
+}
+
+// tasks only see changes they can apply in place, e.g. the mqtt task keeps the startup connection
+fn keep_restart_required(current: &Configuration, conf: Configuration) -> Configuration {
+    Configuration {
+        docker: Docker {
+            persist_backend: current.docker.persist_backend,
+            persist_state: current.docker.persist_state,
+            track_networks: current.docker.track_networks,
+            track_uptime: current.docker.track_uptime,
+            track_volumes: current.docker.track_volumes,
+            ..conf.docker
+        },
+        history: current.history.clone(),
+        host: current.host.clone(),
+        http: current.http.clone(),
+        logging: current.logging.clone(),
+        mqtt: current.mqtt.clone(),
+        swarm: current.swarm.clone(),
+        updates: current.updates.clone(),
+        ..conf
+    }
+}
+
+fn get_modified(files: &[PathBuf]) -> Vec<Option<SystemTime>> {
+    files
+        .iter()
+        .map(|file| fs::metadata(file).and_then(|m| m.modified()).ok())
+        .collect()
+}
+
+#[cfg(test)]
+mod must {
+    use super::{get_restart_required, keep_restart_required};
+
+    fn parse(buffer: &str) -> super::Configuration {
+        serde_yaml::from_str(buffer).unwrap()
+    }
+
+    #[test]
+    fn list_sections_requiring_restart() {
+        // arrange
+        let current = parse(
+            "
+mqtt:
+  client_id: qwert
+  host: yuio
+  port: 1234",
+        );
+
+        let conf = parse(
+            "
+docker:
+  stream_logs_filter:
+    - error
+
+http:
+  address: 0.0.0.0:9184
+
+mqtt:
+  client_id: qwert
+  host: broker
+  port: 1234",
+        );
+
+        // act
+        let sections = get_restart_required(&current, &conf);
+
+        // assert
+        assert_eq!(vec!["http", "mqtt"], sections);
+    }
+
+    #[test]
+    fn apply_docker_and_hassio_in_place() {
+        // arrange
+        let current = parse(
+            "
+mqtt:
+  client_id: qwert
+  host: yuio
+  port: 1234",
+        );
+
+        let conf = parse(
+            "
+docker:
+  stats_interval: 60
+  stream_logs_container:
+    - borg
+
+hassio:
+  discovery: true
+
+mqtt:
+  client_id: qwert
+  host: yuio
+  port: 1234",
+        );
+
+        // act
+        let sections = get_restart_required(&current, &conf);
+
+        // assert
+        assert!(sections.is_empty());
+    }
+
+    #[test]
+    fn keep_sections_requiring_restart() {
+        // arrange
+        let current = parse(
+            "
+mqtt:
+  client_id: qwert
+  host: yuio
+  port: 1234",
+        );
+
+        let conf = parse(
+            "
+docker:
+  stats_interval: 60
+  track_uptime: true
+
+mqtt:
+  client_id: qwert
+  host: yuio
+  port: 1234
+  qos: 2",
+        );
+
+        // act
+        let kept = keep_restart_required(&current, conf);
+
+        // assert
+        assert_eq!(current.mqtt, kept.mqtt);
+        assert_eq!(current.docker.track_uptime, kept.docker.track_uptime);
+        assert_eq!(60, kept.docker.stats_interval);
+    }
+}
@@ -0,0 +1,103 @@
+use std::{future, time::Duration};
+
+use tokio::{
+    signal::{
+        self,
+        unix::{signal, SignalKind},
+    },
+    sync::watch,
+    task::JoinHandle,
+    time,
+};
+use tracing::{error, info, warn};
+
+use crate::{
+    bus::Publisher,
+    persistence::{self, SharedRepository},
+};
+
+// sources stop on cancellation, thus nothing new enters the bus while it drains
+pub type Cancellation = watch::Receiver<bool>;
+
+pub fn channel() -> (watch::Sender<bool>, Cancellation) {
+    watch::channel(false)
+}
+
+// resolves once the shutdown started, the sources keep running if the sender is gone
+pub async fn cancelled(cancellation: &mut Cancellation) {
+    while !*cancellation.borrow() {
+        if cancellation.changed().await.is_err() {
+            future::pending::<()>().await;
+        }
+    }
+}
+
+pub async fn wait() {
+    let mut terminate = match signal(SignalKind::terminate()) {
+        Ok(terminate) => terminate,
+        Err(e) => {
+            error!(
+                "could not listen for SIGTERM, shutting down on SIGINT only: {}",
+                e
+            );
+            let _ = signal::ctrl_c().await;
+            info!("received SIGINT, shutting down");
+            return;
+        }
+    };
+
+    tokio::select! {
+        _ = terminate.recv() => info!("received SIGTERM, shutting down"),
+        _ = signal::ctrl_c() => info!("received SIGINT, shutting down"),
+    }
+}
+
+// the docker sources stop first, then the mqtt task publishes what is left on the bus, marks every
+// device offline and disconnects
+pub async fn run(
+    cancel: watch::Sender<bool>,
+    publisher: Publisher,
+    mqtt: JoinHandle<()>,
+    repo: &SharedRepository,
+    timeout: u64,
+) {
+    let _ = cancel.send(true);
+    publisher.close();
+
+    match time::timeout(Duration::from_secs(timeout), mqtt).await {
+        Ok(Ok(())) => info!("published pending events"),
+        Ok(Err(e)) => error!("mqtt task failed: {}", e),
+        Err(_) => warn!(
+            "dropping pending events, shutdown timed out after {} seconds",
+            timeout
+        ),
+    }
+
+    // persisted state is local, thus flushed even after the timeout
+    persistence::close(repo);
+    info!("shutdown complete");
+}
+
+#[cfg(test)]
+mod must {
+    use std::time::Duration;
+
+    use tokio::time;
+
+    use super::{cancelled, channel};
+
+    #[tokio::test]
+    async fn resolve_once_cancelled() {
+        // arrange
+        let (cancel, mut cancellation) = channel();
+        let pending = time::timeout(Duration::from_millis(10), cancelled(&mut cancellation)).await;
+
+        // act
+        cancel.send(true).unwrap();
+        let resolved = time::timeout(Duration::from_millis(10), cancelled(&mut cancellation)).await;
+
+        // assert
+        assert!(pending.is_err());
+        assert!(resolved.is_ok());
+    }
+}
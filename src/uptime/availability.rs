@@ -0,0 +1,222 @@
+use std::collections::VecDeque;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    events::{ContainerEvent, EventType},
+    persistence::Entity,
+};
+
+pub const DAY: u64 = 24 * 60 * 60;
+pub const WEEK: u64 = 7 * DAY;
+pub const MONTH: u64 = 30 * DAY;
+
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct Uptime {
+    pub container_name: String,
+    pub tracked_since: u64,
+
+    #[serde(default)]
+    pub running_since: Option<u64>,
+
+    #[serde(default)]
+    pub last_start: Option<u64>,
+
+    // seconds up within closed periods, including the ones pruned from periods
+    #[serde(default)]
+    pub total: u64,
+
+    // closed periods as (start, end), only as far back as the longest window
+    #[serde(default)]
+    pub periods: VecDeque<(u64, u64)>,
+}
+
+impl Entity for Uptime {
+    const COLLECTION: &'static str = "uptime";
+    const VERSION: u32 = 1;
+
+    fn key(&self) -> String {
+        self.container_name.to_owned()
+    }
+}
+
+impl Uptime {
+    pub fn new(container_name: &str, now: u64) -> Uptime {
+        Uptime {
+            container_name: container_name.to_owned(),
+            tracked_since: now,
+            ..Uptime::default()
+        }
+    }
+
+    // returns whether the container went up or down
+    pub fn update(&mut self, event: &ContainerEvent, now: u64) -> bool {
+        match event {
+            // the initial state of a running container repeats start on every launch of docker2mqtt
+            ContainerEvent::Restart | ContainerEvent::Start | ContainerEvent::Unpause
+                if self.running_since.is_none() =>
+            {
+                if *event != ContainerEvent::Unpause {
+                    self.last_start = Some(now);
+                }
+
+                self.running_since = Some(now);
+                true
+            }
+            ContainerEvent::Destroy
+            | ContainerEvent::Die
+            | ContainerEvent::Pause
+            | ContainerEvent::Stop => match self.running_since.take() {
+                Some(since) => {
+                    self.periods.push_back((since, now));
+                    self.total += now.saturating_sub(since);
+                    self.prune(now);
+                    true
+                }
+                None => false,
+            },
+            // kill only signals the container, die follows if it exits
+            _ => false,
+        }
+    }
+
+    fn prune(&mut self, now: u64) {
+        let start = now.saturating_sub(MONTH);
+        while self.periods.front().is_some_and(|(_, end)| *end < start) {
+            self.periods.pop_front();
+        }
+    }
+
+    // percentage of the window the container was running, windows reaching back before tracking
+    // started are shortened accordingly
+    pub fn ratio(&self, window: u64, now: u64) -> f64 {
+        let start = now.saturating_sub(window).max(self.tracked_since);
+        if now <= start {
+            return match self.running_since {
+                Some(_) => 100.0,
+                None => 0.0,
+            };
+        }
+
+        let up: u64 = self
+            .periods
+            .iter()
+            .copied()
+            .chain(self.running_since.map(|since| (since, now)))
+            .map(|(from, to)| to.min(now).saturating_sub(from.max(start)))
+            .sum();
+
+        let ratio = up as f64 * 100.0 / (now - start) as f64;
+        (ratio * 100.0).round() / 100.0
+    }
+
+    pub fn downtime(&self, now: u64) -> u64 {
+        let running = self
+            .running_since
+            .map(|since| now.saturating_sub(since))
+            .unwrap_or_default();
+
+        now.saturating_sub(self.tracked_since)
+            .saturating_sub(self.total + running)
+    }
+
+    pub fn events(&self, now: u64) -> Vec<EventType> {
+        let mut events = vec![
+            EventType::Downtime(self.downtime(now)),
+            EventType::Uptime24h(self.ratio(DAY, now)),
+            EventType::Uptime30d(self.ratio(MONTH, now)),
+            EventType::Uptime7d(self.ratio(WEEK, now)),
+        ];
+
+        if let Some(start) = self.last_start {
+            events.push(EventType::TimeSinceStart(now.saturating_sub(start)));
+        }
+
+        events
+    }
+}
+
+#[cfg(test)]
+mod must {
+    use crate::events::{ContainerEvent, EventType};
+
+    use super::{Uptime, DAY, MONTH, WEEK};
+
+    #[test]
+    fn compute_ratio_per_window() {
+        // arrange
+        let mut uptime = Uptime::new("borg", 0);
+        uptime.update(&ContainerEvent::Start, 0);
+        uptime.update(&ContainerEvent::Die, 20 * DAY);
+        uptime.update(&ContainerEvent::Start, 29 * DAY);
+
+        // act
+        let now = 30 * DAY;
+
+        // assert
+        assert_eq!(100.0, uptime.ratio(DAY, now));
+        assert_eq!(14.29, uptime.ratio(WEEK, now));
+        assert_eq!(70.0, uptime.ratio(MONTH, now));
+        assert_eq!(9 * DAY, uptime.downtime(now));
+    }
+
+    #[test]
+    fn shorten_windows_to_tracked_time() {
+        // arrange
+        let mut uptime = Uptime::new("borg", 100);
+        uptime.update(&ContainerEvent::Start, 100);
+        uptime.update(&ContainerEvent::Stop, 175);
+
+        // act
+        let events = uptime.events(200);
+
+        // assert
+        assert_eq!(
+            vec![
+                EventType::Downtime(25),
+                EventType::Uptime24h(75.0),
+                EventType::Uptime30d(75.0),
+                EventType::Uptime7d(75.0),
+                EventType::TimeSinceStart(100),
+            ],
+            events
+        );
+    }
+
+    #[test]
+    fn keep_running_through_kill_and_repeated_start() {
+        // arrange
+        let mut uptime = Uptime::new("borg", 0);
+        uptime.update(&ContainerEvent::Start, 0);
+
+        // act
+        let killed = uptime.update(&ContainerEvent::Kill, 10);
+        let started = uptime.update(&ContainerEvent::Start, 20);
+        uptime.update(&ContainerEvent::Pause, 30);
+        let unpaused = uptime.update(&ContainerEvent::Unpause, 40);
+
+        // assert
+        assert!(!killed);
+        assert!(!started);
+        assert!(unpaused);
+        assert_eq!(Some(40), uptime.running_since);
+        assert_eq!(Some(0), uptime.last_start);
+    }
+
+    #[test]
+    fn prune_periods_older_than_a_month() {
+        // arrange
+        let mut uptime = Uptime::new("borg", 0);
+        uptime.update(&ContainerEvent::Start, 0);
+        uptime.update(&ContainerEvent::Stop, DAY);
+        uptime.update(&ContainerEvent::Start, 40 * DAY);
+
+        // act
+        uptime.update(&ContainerEvent::Stop, 41 * DAY);
+
+        // assert
+        assert_eq!(1, uptime.periods.len());
+        assert_eq!(2 * DAY, uptime.total);
+        assert_eq!(39 * DAY, uptime.downtime(41 * DAY));
+    }
+}
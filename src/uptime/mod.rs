@@ -0,0 +1,208 @@
+use std::{
+    collections::HashMap,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use tokio::{task, time};
+
+use crate::{
+    bus::{Publisher, Subscriber},
+    configuration::Configuration,
+    events::{self, ContainerEvent, Event, EventType},
+    health,
+    persistence::{self, Repository, SharedRepository},
+};
+
+use self::availability::Uptime;
+
+mod availability;
+
+const PUBLISH_INTERVAL: Duration = Duration::from_secs(60);
+
+pub async fn task(
+    mut subscriber: Subscriber,
+    publisher: Publisher,
+    repo: SharedRepository,
+    conf: &Configuration,
+) {
+    if !conf.docker.track_uptime {
+        return;
+    }
+
+    task::spawn(async move {
+        let _alive = health::alive("uptime");
+        let mut interval = time::interval(PUBLISH_INTERVAL);
+
+        // existing containers, kept in memory as the repository does not store anything without
+        // persist_state
+        let mut containers: HashMap<String, Uptime> = HashMap::new();
+
+        loop {
+            tokio::select! {
+                event = subscriber.recv() => {
+                    let event = match event {
+                        Some(event) => event,
+                        None => break,
+                    };
+
+                    let now = get_timestamp();
+                    let mut repo = repo.lock().unwrap();
+                    if let Some(uptime) = handle_event(&event, &mut containers, repo.as_mut(), now) {
+                        publish(&publisher, get_events(&uptime, now));
+                    }
+                }
+                _ = interval.tick() => {
+                    let now = get_timestamp();
+                    for uptime in containers.values() {
+                        publish(&publisher, get_events(uptime, now));
+                    }
+                }
+            }
+        }
+    });
+}
+
+// returns the uptime of the container when it went up or down
+fn handle_event(
+    event: &Event,
+    containers: &mut HashMap<String, Uptime>,
+    repo: &mut dyn Repository,
+    now: u64,
+) -> Option<Uptime> {
+    let state = match &event.event {
+        EventType::State(state) if events::is_container(&event.container_name) => state,
+        _ => return None,
+    };
+
+    // the record survives the removal of a container, thus recreated containers keep their figures
+    let name = &event.container_name;
+    let mut uptime = match containers.remove(name) {
+        Some(uptime) => uptime,
+        None => persistence::find::<Uptime>(repo, name).unwrap_or_else(|| Uptime::new(name, now)),
+    };
+
+    let changed = uptime.update(state, now);
+    if changed {
+        persistence::store(repo, &uptime);
+    }
+
+    // removed containers publish nothing, their topics are cleared on the broker
+    if *state == ContainerEvent::Destroy {
+        return None;
+    }
+
+    containers.insert(name.to_owned(), uptime.clone());
+    match changed {
+        true => Some(uptime),
+        false => None,
+    }
+}
+
+fn get_events(uptime: &Uptime, now: u64) -> Vec<Event> {
+    uptime
+        .events(now)
+        .into_iter()
+        .map(|event| Event {
+            container_name: uptime.container_name.to_owned(),
+            event,
+        })
+        .collect()
+}
+
+fn publish(publisher: &Publisher, events: Vec<Event>) {
+    for event in events {
+        publisher.send(event);
+    }
+}
+
+fn get_timestamp() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod must {
+    use std::collections::HashMap;
+
+    use crate::{
+        events::{ContainerEvent, Event, EventType, HOST},
+        persistence::{self, memory_repository::MemoryRepository},
+    };
+
+    use super::{availability::Uptime, handle_event};
+
+    fn create_event(container_name: &str, state: ContainerEvent) -> Event {
+        Event {
+            container_name: container_name.to_owned(),
+            event: EventType::State(state),
+        }
+    }
+
+    #[test]
+    fn track_containers_only() {
+        // arrange
+        let mut containers = HashMap::new();
+        let mut repo = MemoryRepository::default();
+
+        // act
+        handle_event(
+            &create_event(HOST, ContainerEvent::Start),
+            &mut containers,
+            &mut repo,
+            1,
+        );
+        let started = handle_event(
+            &create_event("borg", ContainerEvent::Start),
+            &mut containers,
+            &mut repo,
+            1,
+        );
+        let renamed = handle_event(
+            &create_event("borg", ContainerEvent::Rename),
+            &mut containers,
+            &mut repo,
+            2,
+        );
+
+        // assert
+        assert_eq!(Some(Some(1)), started.map(|uptime| uptime.running_since));
+        assert_eq!(None, renamed);
+        assert_eq!(1, containers.len());
+    }
+
+    #[test]
+    fn stop_publishing_destroyed_containers() {
+        // arrange
+        let mut containers = HashMap::new();
+        let mut repo = MemoryRepository::default();
+        handle_event(
+            &create_event("borg", ContainerEvent::Start),
+            &mut containers,
+            &mut repo,
+            1,
+        );
+
+        // act
+        let destroyed = handle_event(
+            &create_event("borg", ContainerEvent::Destroy),
+            &mut containers,
+            &mut repo,
+            5,
+        );
+
+        // assert
+        let events: Vec<Event> = containers
+            .values()
+            .flat_map(|uptime| super::get_events(uptime, 60))
+            .collect();
+
+        assert_eq!(None, destroyed);
+        assert!(events.is_empty());
+        assert_eq!(
+            Some(4),
+            persistence::find::<Uptime>(&repo, "borg").map(|uptime| uptime.total)
+        );
+    }
+}